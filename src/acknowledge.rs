@@ -0,0 +1,325 @@
+use serde::{Deserialize, Deserializer};
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::config_file::read_config_file;
+use crate::glob;
+use crate::types::{Acknowledged, GradleList, ProcessedDependencyObject};
+
+/// Reviewed changes that should not be reported as new on every run, read from a TOML or YAML file:
+/// ```toml
+/// [[acknowledge]]
+/// dependency = "org.yaml:snakeyaml"
+/// from = "1.33"
+/// to = "2.0"
+/// reason = "Reviewed in #123"
+/// expires = "2024-06-30"
+/// ```
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Acknowledgements {
+  #[serde(rename = "acknowledge", alias = "acknowledgements", default)]
+  pub entries: Vec<Acknowledgement>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Acknowledgement {
+  /// `group:artifact` glob.
+  pub dependency: String,
+  /// Glob of the version before; any when omitted.
+  pub from: Option<String>,
+  /// Glob of the version after; any when omitted.
+  pub to: Option<String>,
+  /// Configuration globs; all configurations when empty.
+  #[serde(default)]
+  pub configurations: Vec<String>,
+  pub reason: String,
+  /// Date (`YYYY-MM-DD`) after which the entry no longer applies, quoted or as a TOML date.
+  #[serde(default, deserialize_with = "deserialize_date")]
+  pub expires: Option<String>,
+}
+
+/// Expiry date as written in the file: TOML reads an unquoted date as a datetime rather than a string.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawDate {
+  Text(String),
+  Toml(toml::value::Datetime),
+}
+
+fn deserialize_date<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  Ok(Option::<RawDate>::deserialize(deserializer)?.map(|date| {
+    match date {
+      RawDate::Text(text) => text,
+      /* a datetime with a time of day only is left as is, for the validation to reject it */
+      RawDate::Toml(datetime) => datetime
+        .date
+        .map_or(datetime.to_string(), |d| d.to_string()),
+    }
+  }))
+}
+
+impl Acknowledgements {
+  /// Reads an acknowledgements file, see `read_config_file`.
+  pub fn from_file<P>(path: P) -> std::io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    read_config_file(path).and_then(Self::validate)
+  }
+
+  /// Rejects the entries whose expiry date isn't a valid `YYYY-MM-DD`.
+  fn validate(acknowledgements: Acknowledgements) -> std::io::Result<Self> {
+    for entry in acknowledgements.entries.iter() {
+      if let Some(expires) = entry
+        .expires
+        .as_deref()
+        .filter(|e| parse_date(e).is_none())
+      {
+        return Err(std::io::Error::other(format!(
+          "invalid expiry date \"{}\" of the acknowledgement of {} ({}), expected YYYY-MM-DD",
+          expires, entry.dependency, entry.reason
+        )));
+      }
+    }
+
+    Ok(acknowledgements)
+  }
+
+  /// Marks the changes matching an unexpired entry as acknowledged.
+  /// Returns a warning for each entry that expired before `today` (`YYYY-MM-DD`),
+  /// whose changes are reported again.
+  pub fn apply(&self, values: &mut [ProcessedDependencyObject], today: &str) -> Vec<String> {
+    let mut warnings: Vec<String> = Vec::new();
+
+    for entry in self.entries.iter() {
+      if entry.is_expired(today) {
+        warnings.push(format!(
+          "acknowledgement of {} expired on {} ({})",
+          entry.dependency,
+          entry.expires.as_deref().unwrap_or_default(),
+          entry.reason
+        ));
+        continue;
+      }
+
+      for value in values.iter_mut() {
+        let coordinate = format!("{}:{}", value.dependency.namespace, value.dependency.name);
+
+        if !glob::matches(&entry.dependency, &coordinate) {
+          continue;
+        }
+
+        for list in value
+          .gradle_versions
+          .iter_mut()
+          .filter(|l| l.change_kind().is_change() && !l.is_acknowledged() && entry.matches(l))
+        {
+          list.acknowledged = Some(Acknowledged {
+            reason: entry.reason.clone(),
+            expires: entry.expires.clone(),
+          });
+        }
+      }
+    }
+
+    warnings
+  }
+}
+
+impl Acknowledgement {
+  fn is_expired(&self, today: &str) -> bool {
+    match (self.expires.as_deref().and_then(parse_date), parse_date(today)) {
+      (Some(expires), Some(today)) => expires < today,
+      _ => false,
+    }
+  }
+
+  fn matches(&self, list: &GradleList) -> bool {
+    (self.configurations.is_empty() || glob::matches_any(&self.configurations, &list.gradle_config_name))
+      && self
+        .from
+        .as_deref()
+        .is_none_or(|from| glob::matches(from, &list.version_before))
+      && self
+        .to
+        .as_deref()
+        .is_none_or(|to| glob::matches(to, &list.version_after))
+  }
+}
+
+/// Year, month and day of a `YYYY-MM-DD` date, or `None` if it isn't one or doesn't exist.
+fn parse_date(date: &str) -> Option<(u32, u32, u32)> {
+  let mut parts = date.splitn(3, '-');
+  let mut next = |len: usize| {
+    parts
+      .next()
+      .filter(|p| p.len() == len && p.bytes().all(|b| b.is_ascii_digit()))
+      .and_then(|p| p.parse::<u32>().ok())
+  };
+  let (year, month, day) = (next(4)?, next(2)?, next(2)?);
+
+  let days_in_month = match month {
+    2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+    2 => 28,
+    4 | 6 | 9 | 11 => 30,
+    1..=12 => 31,
+    _ => return None,
+  };
+
+  (1..=days_in_month)
+    .contains(&day)
+    .then_some((year, month, day))
+}
+
+/// Today's UTC date as `YYYY-MM-DD`.
+pub fn today() -> String {
+  let days = SystemTime::now()
+    .duration_since(UNIX_EPOCH)
+    .map_or(0, |d| d.as_secs() / 86_400) as i64;
+
+  /* days since 1970-01-01 to civil date, from Howard Hinnant's `civil_from_days` */
+  let z = days + 719_468;
+  let era = z.div_euclid(146_097);
+  let doe = z - era * 146_097;
+  let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+  let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+  let mp = (5 * doy + 2) / 153;
+  let day = doy - (153 * mp + 2) / 5 + 1;
+  let month = if mp < 10 { mp + 3 } else { mp - 9 };
+  let year = yoe + era * 400 + i64::from(month <= 2);
+
+  format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::config_file::parse_config;
+  use crate::types::test_support::dep;
+
+  fn parse(content: &str, is_yaml: bool) -> std::io::Result<Acknowledgements> {
+    parse_config(content, is_yaml).and_then(Acknowledgements::validate)
+  }
+
+  const ACKNOWLEDGEMENTS: &str = r#"
+[[acknowledge]]
+dependency = "org.yaml:snakeyaml"
+from = "1.*"
+to = "2.0"
+configurations = ["runtimeClasspath"]
+reason = "Reviewed in #123"
+
+[[acknowledge]]
+dependency = "com.google.guava:*"
+reason = "Guava bump, see #99"
+expires = "2024-01-31"
+"#;
+
+  #[test]
+  fn it_acknowledges_matching_changes() {
+    let acks: Acknowledgements = toml::from_str(ACKNOWLEDGEMENTS).unwrap();
+
+    let mut values = vec![dep(
+      "org.yaml:snakeyaml",
+      vec![
+        ("runtimeClasspath", "1.33", "2.0"),
+        ("compileClasspath", "1.33", "2.0"),
+        ("testRuntimeClasspath", "1.33", "2.1"),
+      ],
+    )];
+
+    let warnings = acks.apply(&mut values, "2024-01-01");
+
+    assert!(warnings.is_empty());
+    let lists = &values[0].gradle_versions;
+    assert_eq!(lists[0].acknowledged.as_ref().unwrap().reason, "Reviewed in #123");
+    assert!(!lists[1].is_acknowledged());
+    assert!(!lists[2].is_acknowledged());
+  }
+
+  #[test]
+  fn it_resurfaces_expired_entries_with_a_warning() {
+    let acks: Acknowledgements = toml::from_str(ACKNOWLEDGEMENTS).unwrap();
+
+    let mut values = vec![dep(
+      "com.google.guava:guava",
+      vec![("runtimeClasspath", "31.1-jre", "32.1.2-jre")],
+    )];
+
+    assert!(acks.apply(&mut values, "2024-01-31").is_empty());
+    assert!(values[0].gradle_versions[0].is_acknowledged());
+
+    values[0].gradle_versions[0].acknowledged = None;
+    let warnings = acks.apply(&mut values, "2024-02-01");
+
+    assert_eq!(
+      warnings,
+      vec!["acknowledgement of com.google.guava:* expired on 2024-01-31 (Guava bump, see #99)"]
+    );
+    assert!(!values[0].gradle_versions[0].is_acknowledged());
+  }
+
+  #[test]
+  fn it_reads_expiry_dates_quoted_or_as_toml_dates() {
+    let acks = parse(
+      "[[acknowledge]]\ndependency = \"a:b\"\nreason = \"r\"\nexpires = 2024-01-31\n",
+      false,
+    )
+    .unwrap();
+    assert_eq!(acks.entries[0].expires.as_deref(), Some("2024-01-31"));
+
+    let acks = parse(
+      "acknowledge:\n  - dependency: a:b\n    reason: r\n    expires: 2024-01-31\n",
+      true,
+    )
+    .unwrap();
+    assert!(acks.entries[0].is_expired("2024-02-01"));
+    assert!(!acks.entries[0].is_expired("2024-01-31"));
+  }
+
+  #[test]
+  fn it_rejects_malformed_expiry_dates() {
+    for expires in ["\"2024-1-31\"", "\"31/01/2024\"", "\"2023-02-29\"", "10:30:00"] {
+      let error = parse(
+        &format!(
+          "[[acknowledge]]\ndependency = \"a:b\"\nreason = \"r\"\nexpires = {}\n",
+          expires
+        ),
+        false,
+      )
+      .unwrap_err();
+
+      assert!(
+        error
+          .to_string()
+          .contains("acknowledgement of a:b (r)"),
+        "{}",
+        error
+      );
+    }
+  }
+
+  #[test]
+  fn it_rejects_misspelled_fields() {
+    let error = parse(
+      "[[acknowledge]]\ndependency = \"a:b\"\nreason = \"r\"\nexpire = \"2024-01-31\"\n",
+      false,
+    )
+    .unwrap_err();
+
+    assert!(error.to_string().contains("unknown field `expire`"), "{}", error);
+  }
+
+  #[test]
+  fn it_formats_today_as_an_iso_date() {
+    let today = today();
+
+    assert_eq!(today.len(), 10);
+    assert!(today.as_str() > "2023-01-01");
+  }
+}
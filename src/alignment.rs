@@ -0,0 +1,244 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::str::FromStr;
+
+use crate::glob;
+use crate::types::{TempList, NOT_APPLICABLE};
+
+/// Exit code of `--alignment` when misaligned families are found, distinct from the other checks.
+pub const MISALIGNMENT_EXIT_CODE: i32 = 11;
+
+/// Artifacts released together, which normally resolve to the same version.
+const BUILTIN_FAMILIES: [(&str, &[&str]); 10] = [
+  ("jackson", &["com.fasterxml.jackson.*:jackson-*"]),
+  ("netty", &["io.netty:netty-*", "!io.netty:netty-tcnative*"]),
+  ("spring-framework", &["org.springframework:spring-*"]),
+  ("spring-boot", &["org.springframework.boot:spring-boot*"]),
+  (
+    "kotlin",
+    &[
+      "org.jetbrains.kotlin:kotlin-stdlib*",
+      "org.jetbrains.kotlin:kotlin-reflect",
+    ],
+  ),
+  ("kotlinx-coroutines", &["org.jetbrains.kotlinx:kotlinx-coroutines-*"]),
+  ("grpc", &["io.grpc:grpc-*"]),
+  ("junit-jupiter", &["org.junit.jupiter:junit-jupiter*"]),
+  ("log4j", &["org.apache.logging.log4j:log4j-*"]),
+  ("micrometer", &["io.micrometer:micrometer-*"]),
+];
+
+/// A named set of `group:artifact` globs, where globs starting with `!` exclude artifacts.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Family {
+  pub name: String,
+  pub members: Vec<String>,
+}
+
+/// Parses a `name=glob,glob` family definition.
+impl FromStr for Family {
+  type Err = String;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    match s.split_once('=') {
+      Some((name, members)) if !name.is_empty() && !members.is_empty() => Ok(Family {
+        name: name.to_string(),
+        members: members
+          .split(',')
+          .map(|m| m.trim().to_string())
+          .collect(),
+      }),
+      _ => Err(format!("expected `name=glob[,glob...]`, got `{}`", s)),
+    }
+  }
+}
+
+impl Family {
+  fn contains(&self, coordinate: &str) -> bool {
+    let (excluded, included): (Vec<&String>, Vec<&String>) = self.members.iter().partition(|m| m.starts_with('!'));
+
+    included.iter().any(|m| glob::matches(m, coordinate))
+      && !excluded
+        .iter()
+        .any(|m| glob::matches(&m[1..], coordinate))
+  }
+}
+
+/// The built-in families, after the given ones so that those take precedence.
+pub fn families(user_families: &[Family]) -> Vec<Family> {
+  let mut families = user_families.to_vec();
+
+  families.extend(BUILTIN_FAMILIES.iter().map(|(name, members)| Family {
+    name: name.to_string(),
+    members: members.iter().map(|m| m.to_string()).collect(),
+  }));
+
+  families
+}
+
+/// A family whose members resolve to more than one version in a configuration.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Misalignment {
+  pub family: String,
+  pub gradle_config_name: String,
+  /// Members of the family in the configuration, sorted by coordinate.
+  pub members: Vec<AlignedMember>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AlignedMember {
+  pub coordinate: String,
+  pub version: String,
+}
+
+impl Misalignment {
+  /// Distinct versions of the members, sorted.
+  pub fn versions(&self) -> BTreeSet<&str> {
+    self
+      .members
+      .iter()
+      .map(|m| m.version.as_str())
+      .collect()
+  }
+}
+
+/// Detects the misaligned families of each configuration, sorted by family then configuration. \
+/// A dependency belongs to the first family it matches or, with `by_group`, to the family of its group
+/// when it matches none.
+pub fn detect(resolved: &[(String, Vec<TempList>)], families: &[Family], by_group: bool) -> Vec<Misalignment> {
+  let mut members: BTreeMap<(String, String), Vec<AlignedMember>> = BTreeMap::new();
+
+  for (coordinate, versions) in resolved.iter() {
+    let family = match families.iter().find(|f| f.contains(coordinate)) {
+      Some(f) => f.name.clone(),
+      None if by_group => match coordinate.split_once(':') {
+        Some((group, _)) => group.to_string(),
+        None => continue,
+      },
+      None => continue,
+    };
+
+    for temp in versions.iter().filter(|t| t.version != NOT_APPLICABLE) {
+      members
+        .entry((family.clone(), temp.gradle_config_name.clone()))
+        .or_default()
+        .push(AlignedMember {
+          coordinate: coordinate.clone(),
+          version: temp.version.clone(),
+        });
+    }
+  }
+
+  members
+    .into_iter()
+    .map(|((family, gradle_config_name), mut members)| {
+      members.sort_by(|a, b| a.coordinate.cmp(&b.coordinate));
+      Misalignment {
+        family,
+        gradle_config_name,
+        members,
+      }
+    })
+    .filter(|m| m.versions().len() > 1)
+    .collect()
+}
+
+/// The misalignments after of the families that were aligned in the same configuration before.
+pub fn newly_misaligned(before: &[Misalignment], after: Vec<Misalignment>) -> Vec<Misalignment> {
+  after
+    .into_iter()
+    .filter(|a| {
+      !before
+        .iter()
+        .any(|b| b.family == a.family && b.gradle_config_name == a.gradle_config_name)
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::temp;
+
+  fn resolved(deps: Vec<(&str, Vec<(&str, &str)>)>) -> Vec<(String, Vec<TempList>)> {
+    deps
+      .into_iter()
+      .map(|(coordinate, versions)| {
+        (
+          coordinate.to_string(),
+          versions
+            .into_iter()
+            .map(|(config, version)| temp(config, version))
+            .collect(),
+        )
+      })
+      .collect()
+  }
+
+  #[test]
+  fn it_detects_families_resolving_to_mixed_versions() {
+    let deps = resolved(vec![
+      (
+        "io.netty:netty-handler",
+        vec![
+          ("runtimeClasspath", "4.1.100.Final"),
+          ("testRuntimeClasspath", "4.1.100.Final"),
+        ],
+      ),
+      (
+        "io.netty:netty-codec",
+        vec![
+          ("runtimeClasspath", "4.1.94.Final"),
+          ("testRuntimeClasspath", "4.1.100.Final"),
+        ],
+      ),
+      (
+        "io.netty:netty-tcnative-classes",
+        vec![("runtimeClasspath", "2.0.61.Final")],
+      ),
+      ("com.acme:acme-core", vec![("runtimeClasspath", "1.0")]),
+      ("com.acme:acme-client", vec![("runtimeClasspath", "1.1")]),
+    ]);
+
+    let res = detect(&deps, &families(&[]), false);
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].family, "netty");
+    assert_eq!(res[0].gradle_config_name, "runtimeClasspath");
+    assert_eq!(res[0].members.len(), 2);
+
+    let acme: Family = "acme=com.acme:*".parse().unwrap();
+    assert_eq!(detect(&deps, &families(&[acme]), false).len(), 2);
+    assert_eq!(detect(&deps, &families(&[]), true).len(), 2);
+  }
+
+  #[test]
+  fn it_reports_only_newly_misaligned_families() {
+    let before = detect(
+      &resolved(vec![
+        ("io.grpc:grpc-api", vec![("runtimeClasspath", "1.58.0")]),
+        ("io.grpc:grpc-core", vec![("runtimeClasspath", "1.57.0")]),
+        ("io.netty:netty-handler", vec![("runtimeClasspath", "4.1.100.Final")]),
+        ("io.netty:netty-codec", vec![("runtimeClasspath", "4.1.100.Final")]),
+      ]),
+      &families(&[]),
+      false,
+    );
+    let after = detect(
+      &resolved(vec![
+        ("io.grpc:grpc-api", vec![("runtimeClasspath", "1.59.0")]),
+        ("io.grpc:grpc-core", vec![("runtimeClasspath", "1.57.0")]),
+        ("io.netty:netty-handler", vec![("runtimeClasspath", "4.1.101.Final")]),
+        ("io.netty:netty-codec", vec![("runtimeClasspath", "4.1.100.Final")]),
+      ]),
+      &families(&[]),
+      false,
+    );
+
+    let res = newly_misaligned(&before, after);
+
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].family, "netty");
+  }
+}
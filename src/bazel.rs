@@ -0,0 +1,355 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::parser::DependencyParser;
+use crate::types::{ParsedDependency, Version};
+
+/// Configuration the artifacts of a `maven_install.json` are put on.
+pub const MAVEN_INSTALL_CONFIGURATION: &str = "maven_install";
+
+/// Classifiers of the artifacts fetched next to the jars, which aren't dependencies of their own.
+const SKIPPED_CLASSIFIERS: [&str; 2] = ["sources", "javadoc"];
+
+/// `maven_install.json` of rules_jvm_external, in either of its formats.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum MavenInstall {
+  V1 { dependency_tree: V1Tree },
+  V2(V2Lock),
+}
+
+#[derive(Deserialize)]
+struct V1Tree {
+  #[serde(default)]
+  conflict_resolution: BTreeMap<String, String>,
+  dependencies: Vec<V1Artifact>,
+}
+
+/// Artifact of the v1 format, with versioned coordinates like `com.google.guava:guava:32.1.2-jre`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct V1Artifact {
+  coord: String,
+  #[serde(default)]
+  direct_dependencies: Vec<String>,
+}
+
+/// Lock of the v2 format, keyed by unversioned coordinates like `com.google.guava:guava`.
+#[derive(Deserialize)]
+struct V2Lock {
+  #[serde(default)]
+  conflict_resolution: BTreeMap<String, String>,
+  artifacts: BTreeMap<String, V2Artifact>,
+  #[serde(default)]
+  dependencies: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct V2Artifact {
+  version: String,
+}
+
+/// Artifacts of a lock keyed by `group:artifact`, with their direct dependencies.
+#[derive(Debug, Default)]
+struct Lock {
+  versions: BTreeMap<String, String>,
+  edges: FxHashMap<String, BTreeSet<String>>,
+  /// Version requested before a conflict resolution, for the artifacts whose version was overridden.
+  requested: FxHashMap<String, String>,
+}
+
+/// Parses a `maven_install.json` of rules_jvm_external into the next input of `parser`. \
+/// All the artifacts are put on the `maven_install` configuration, as a tree rooted at the artifacts
+/// no other one depends on. Artifacts with a classifier are merged into their main artifact.
+pub fn parse_file<P>(parser: &mut DependencyParser, path: P) -> std::io::Result<()>
+where
+  P: AsRef<Path>,
+{
+  parse(parser, &fs::read_to_string(path)?)
+}
+
+fn parse(parser: &mut DependencyParser, content: &str) -> std::io::Result<()> {
+  let lock = match serde_json::from_str::<MavenInstall>(content)? {
+    MavenInstall::V1 { dependency_tree } => from_v1(dependency_tree),
+    MavenInstall::V2(lock) => from_v2(lock),
+  };
+
+  parser.start_configuration(MAVEN_INSTALL_CONFIGURATION);
+
+  let dependencies: FxHashSet<&String> = lock.edges.values().flatten().collect();
+  let mut expanded: FxHashSet<&str> = FxHashSet::default();
+
+  for key in lock
+    .versions
+    .keys()
+    .filter(|k| !dependencies.contains(k))
+  {
+    add_subtree(parser, &lock, key, 0, &mut expanded);
+  }
+  /* artifacts only reachable through a cycle */
+  for key in lock.versions.keys() {
+    if !expanded.contains(key.as_str()) {
+      add_subtree(parser, &lock, key, 0, &mut expanded);
+    }
+  }
+
+  parser.end_input();
+  Ok(())
+}
+
+/// Adds an artifact and, the first time it is met, its dependencies below it, like Gradle marks repeated subtrees.
+fn add_subtree<'a>(
+  parser: &mut DependencyParser,
+  lock: &'a Lock,
+  key: &'a str,
+  depth: usize,
+  expanded: &mut FxHashSet<&'a str>,
+) {
+  let version = match lock.versions.get(key) {
+    Some(v) => v,
+    None => return,
+  };
+  let (namespace, name) = key.split_once(':').unwrap();
+  let repeated = !expanded.insert(key);
+
+  parser.add_dependency(
+    depth,
+    repeated,
+    ParsedDependency {
+      name: name.to_string(),
+      namespace: namespace.to_string(),
+      transitive: Version::Transitive(lock.requested.get(key).unwrap_or(version).clone()),
+      pinned: match lock.requested.contains_key(key) {
+        true => Version::Pinned(version.clone()),
+        false => Version::NotApplicable,
+      },
+    },
+  );
+
+  if repeated {
+    return;
+  }
+  for dependency in lock.edges.get(key).into_iter().flatten() {
+    add_subtree(parser, lock, dependency, depth + 1, expanded);
+  }
+}
+
+fn from_v1(tree: V1Tree) -> Lock {
+  let mut lock = Lock::default();
+
+  for artifact in tree.dependencies.iter() {
+    let (key, version) = match split_coordinate(&artifact.coord, true) {
+      Some((key, Some(version))) => (key, version),
+      _ => continue,
+    };
+
+    lock.versions.insert(key.clone(), version.to_string());
+    let edges = lock.edges.entry(key.clone()).or_default();
+    edges.extend(
+      artifact
+        .direct_dependencies
+        .iter()
+        .filter_map(|d| split_coordinate(d, true))
+        .map(|(dependency, _)| dependency)
+        .filter(|dependency| dependency != &key),
+    );
+  }
+
+  lock.requested = requested_versions(&tree.conflict_resolution);
+  lock
+}
+
+fn from_v2(v2: V2Lock) -> Lock {
+  let mut lock = Lock::default();
+
+  for (coord, artifact) in v2.artifacts.iter() {
+    if let Some((key, _)) = split_coordinate(coord, false) {
+      lock.versions.insert(key, artifact.version.clone());
+    }
+  }
+
+  for (coord, dependencies) in v2.dependencies.iter() {
+    let key = match split_coordinate(coord, false) {
+      Some((key, _)) => key,
+      None => continue,
+    };
+
+    let edges = lock.edges.entry(key.clone()).or_default();
+    edges.extend(
+      dependencies
+        .iter()
+        .filter_map(|d| split_coordinate(d, false))
+        .map(|(dependency, _)| dependency)
+        .filter(|dependency| dependency != &key),
+    );
+  }
+
+  lock.requested = requested_versions(&v2.conflict_resolution);
+  lock
+}
+
+/// Requested version of each overridden artifact, from conflict resolutions like
+/// `"com.google.guava:guava:31.1-jre": "com.google.guava:guava:32.1.2-jre"`.
+fn requested_versions(conflict_resolution: &BTreeMap<String, String>) -> FxHashMap<String, String> {
+  conflict_resolution
+    .keys()
+    .filter_map(|requested| match split_coordinate(requested, true) {
+      Some((key, Some(version))) => Some((key, version.to_string())),
+      _ => None,
+    })
+    .collect()
+}
+
+/// Splits `group:artifact[:packaging[:classifier]]` into its `group:artifact` key and, when `versioned`,
+/// the trailing version. Sources and javadoc artifacts are skipped.
+fn split_coordinate(coord: &str, versioned: bool) -> Option<(String, Option<&str>)> {
+  let mut parts: Vec<&str> = coord.split(':').collect();
+  let version = match versioned {
+    true if parts.len() >= 3 => parts.pop(),
+    true => return None,
+    false => None,
+  };
+
+  if parts.len() < 2 || parts.len() > 4 || parts[0].is_empty() || parts[1].is_empty() {
+    return None;
+  }
+  if parts.len() == 4 && SKIPPED_CLASSIFIERS.contains(&parts[3]) {
+    return None;
+  }
+
+  Some((format!("{}:{}", parts[0], parts[1]), version))
+}
+
+/// Checks whether a file is a `maven_install.json` rather than a previous JSON output.
+pub fn is_maven_install<P>(path: P) -> bool
+where
+  P: AsRef<Path>,
+{
+  let content = match fs::read_to_string(path) {
+    Ok(c) => c,
+    Err(_) => return false,
+  };
+
+  match serde_json::from_str::<serde_json::Value>(&content) {
+    Ok(json) => json.get("dependency_tree").is_some() || json.get("artifacts").is_some_and(|a| a.is_object()),
+    Err(_) => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tree(parser: &DependencyParser) -> Vec<String> {
+    fn walk(nodes: &[crate::types::TreeNode], depth: usize, out: &mut Vec<String>) {
+      for node in nodes {
+        out.push(format!(
+          "{}{}:{}{}",
+          "  ".repeat(depth),
+          node.name,
+          node.version,
+          if node.repeated { " (*)" } else { "" }
+        ));
+        walk(&node.children, depth + 1, out);
+      }
+    }
+
+    let mut out = Vec::new();
+    walk(&parser.trees[0][0].roots, 0, &mut out);
+    out
+  }
+
+  #[test]
+  fn it_parses_the_v1_format_with_its_edges() {
+    let mut parser = DependencyParser::new();
+    parse(
+      &mut parser,
+      r#"{
+        "dependency_tree": {
+          "conflict_resolution": {
+            "com.google.guava:guava:31.1-jre": "com.google.guava:guava:32.1.2-jre"
+          },
+          "dependencies": [
+            {
+              "coord": "com.google.guava:failureaccess:1.0.1",
+              "directDependencies": []
+            },
+            {
+              "coord": "com.google.guava:guava:32.1.2-jre",
+              "directDependencies": ["com.google.guava:failureaccess:1.0.1"]
+            },
+            {
+              "coord": "com.google.guava:guava:jar:sources:32.1.2-jre",
+              "directDependencies": []
+            },
+            {
+              "coord": "com.google.inject:guice:7.0.0",
+              "directDependencies": ["com.google.guava:guava:32.1.2-jre"]
+            },
+            {
+              "coord": "com.google.truth:truth:1.1.5",
+              "directDependencies": ["com.google.guava:guava:32.1.2-jre"]
+            }
+          ],
+          "version": "0.1.0"
+        }
+      }"#,
+    )
+    .unwrap();
+
+    assert_eq!(parser.trees[0][0].gradle_config_name, MAVEN_INSTALL_CONFIGURATION);
+    assert_eq!(
+      tree(&parser),
+      vec![
+        "guice:7.0.0",
+        "  guava:32.1.2-jre",
+        "    failureaccess:1.0.1",
+        "truth:1.1.5",
+        "  guava:32.1.2-jre (*)",
+      ]
+    );
+
+    let guava = &parser.dep_maps[0]["com.google.guava:guava"].gradle_entries[0].versions;
+    assert_eq!(guava.transitive[0].to_string(), "31.1-jre");
+    assert_eq!(guava.pinned.to_string(), "32.1.2-jre");
+    assert_eq!(parser.dep_maps[0].len(), 4);
+  }
+
+  #[test]
+  fn it_parses_the_v2_format_and_cycles() {
+    let mut parser = DependencyParser::new();
+    parse(
+      &mut parser,
+      r#"{
+        "__AUTOGENERATED_FILE_DO_NOT_MODIFY_THIS_FILE_MANUALLY": "THERE_IS_NO_DATA_ONLY_ZUUL",
+        "artifacts": {
+          "io.netty:netty-transport-native-epoll:jar:linux-x86_64": { "shasums": {}, "version": "4.1.100.Final" },
+          "io.netty:netty-transport": { "shasums": {}, "version": "4.1.100.Final" },
+          "org.example:a": { "shasums": {}, "version": "1.0" },
+          "org.example:b": { "shasums": {}, "version": "2.0" }
+        },
+        "dependencies": {
+          "io.netty:netty-transport-native-epoll:jar:linux-x86_64": ["io.netty:netty-transport"],
+          "org.example:a": ["org.example:b"],
+          "org.example:b": ["org.example:a"]
+        },
+        "version": "2"
+      }"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+      tree(&parser),
+      vec![
+        "netty-transport-native-epoll:4.1.100.Final",
+        "  netty-transport:4.1.100.Final",
+        "a:1.0",
+        "  b:2.0",
+        "    a:1.0 (*)",
+      ]
+    );
+  }
+}
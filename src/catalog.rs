@@ -0,0 +1,511 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use crate::types::{not_applicable, nullable, ChangeKind, TempList, NOT_APPLICABLE};
+
+/// Characters of the version ranges and dynamic versions, which can't be compared to a resolved version.
+const DYNAMIC_VERSION_CHARS: [char; 6] = ['[', ']', '(', ')', ',', '+'];
+
+/// A `libs.versions.toml` version catalog, with the versions of its libraries and plugins resolved
+/// from their `version.ref`:
+/// ```toml
+/// [versions]
+/// jackson = "2.15.3"
+///
+/// [libraries]
+/// jackson-databind = { module = "com.fasterxml.jackson.core:jackson-databind", version.ref = "jackson" }
+///
+/// [bundles]
+/// jackson = ["jackson-databind"]
+///
+/// [plugins]
+/// spring-boot = { id = "org.springframework.boot", version = "3.1.4" }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Catalog {
+  pub versions: BTreeMap<String, String>,
+  pub libraries: BTreeMap<String, CatalogModule>,
+  pub bundles: BTreeMap<String, Vec<String>>,
+  pub plugins: BTreeMap<String, CatalogModule>,
+}
+
+/// Library or plugin of a catalog.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CatalogModule {
+  /// `group:artifact` of a library, id of a plugin.
+  pub module: String,
+  /// Declared version, `None` when the version comes from elsewhere, e.g. a platform.
+  pub version: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum CatalogSection {
+  Versions,
+  Libraries,
+  Bundles,
+  Plugins,
+}
+
+impl fmt::Display for CatalogSection {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let val = match self {
+      CatalogSection::Versions => "versions",
+      CatalogSection::Libraries => "libraries",
+      CatalogSection::Bundles => "bundles",
+      CatalogSection::Plugins => "plugins",
+    };
+
+    write!(f, "{}", val)
+  }
+}
+
+/// Entry of the catalog added, removed or changed between two revisions.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CatalogChange {
+  pub section: CatalogSection,
+  pub alias: String,
+  /// Version, `module:version`, `id:version` or comma-separated aliases of a bundle.
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
+  pub value_before: String,
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
+  pub value_after: String,
+  pub kind: ChangeKind,
+}
+
+/// Resolved version of a dependency declared in the catalog, in some configurations.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CatalogAnnotation {
+  pub coordinate: String,
+  /// `libraries`, or `plugins` for plugin markers.
+  pub section: CatalogSection,
+  pub alias: String,
+  pub declared_version: Option<String>,
+  pub resolved_version: String,
+  pub gradle_config_names: Vec<String>,
+  /// Whether the resolved version differs from the declared one.
+  pub mismatch: bool,
+}
+
+/// Catalog changes and annotations of a diff.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CatalogReport {
+  /// Entries changed between the catalogs given with `--catalog-before` and `--catalog`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub changes: Vec<CatalogChange>,
+  /// Resolved dependencies after declared in the catalog given with `--catalog`, sorted by coordinate.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub annotations: Vec<CatalogAnnotation>,
+}
+
+impl CatalogReport {
+  pub fn is_empty(&self) -> bool {
+    self.changes.is_empty() && self.annotations.is_empty()
+  }
+
+  pub fn mismatches(&self) -> impl Iterator<Item = &CatalogAnnotation> {
+    self.annotations.iter().filter(|a| a.mismatch)
+  }
+}
+
+impl Catalog {
+  pub fn from_file<P>(path: P) -> std::io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    Self::parse(&fs::read_to_string(path)?)
+  }
+
+  pub fn parse(content: &str) -> std::io::Result<Self> {
+    let table: toml::Table = toml::from_str(content).map_err(std::io::Error::other)?;
+    let section = |name: &str| {
+      table
+        .get(name)
+        .and_then(|s| s.as_table())
+        .into_iter()
+        .flatten()
+    };
+
+    let mut catalog = Catalog::default();
+
+    for (alias, value) in section("versions") {
+      match declared_version(value) {
+        Some(version) => catalog.versions.insert(alias.clone(), version),
+        None => return Err(invalid("versions", alias, "expected a version")),
+      };
+    }
+
+    for (alias, value) in section("libraries") {
+      let library = match value {
+        toml::Value::String(notation) => {
+          let mut parts = notation.splitn(3, ':');
+          match (parts.next(), parts.next(), parts.next()) {
+            (Some(group), Some(artifact), version) if !group.is_empty() && !artifact.is_empty() => CatalogModule {
+              module: format!("{}:{}", group, artifact),
+              version: version.map(|v| v.to_string()),
+            },
+            _ => return Err(invalid("libraries", alias, "expected `group:artifact:version`")),
+          }
+        }
+        toml::Value::Table(t) => {
+          let module = match (t.get("module"), t.get("group"), t.get("name")) {
+            (Some(toml::Value::String(module)), _, _) => module.clone(),
+            (None, Some(toml::Value::String(group)), Some(toml::Value::String(name))) => format!("{}:{}", group, name),
+            _ => {
+              return Err(invalid(
+                "libraries",
+                alias,
+                "expected a `module` or a `group` and a `name`",
+              ))
+            }
+          };
+          CatalogModule {
+            module,
+            version: catalog.version_of(t, "libraries", alias)?,
+          }
+        }
+        _ => return Err(invalid("libraries", alias, "expected a string or a table")),
+      };
+      catalog.libraries.insert(alias.clone(), library);
+    }
+
+    for (alias, value) in section("bundles") {
+      let libraries: Option<Vec<String>> = value.as_array().and_then(|a| {
+        a.iter()
+          .map(|l| l.as_str().map(|l| l.to_string()))
+          .collect()
+      });
+      match libraries {
+        Some(libraries) => catalog.bundles.insert(alias.clone(), libraries),
+        None => return Err(invalid("bundles", alias, "expected an array of library aliases")),
+      };
+    }
+
+    for (alias, value) in section("plugins") {
+      let plugin = match value {
+        toml::Value::String(notation) => match notation.split_once(':') {
+          Some((id, version)) => CatalogModule {
+            module: id.to_string(),
+            version: Some(version.to_string()),
+          },
+          None => return Err(invalid("plugins", alias, "expected `id:version`")),
+        },
+        toml::Value::Table(t) => match t.get("id") {
+          Some(toml::Value::String(id)) => CatalogModule {
+            module: id.clone(),
+            version: catalog.version_of(t, "plugins", alias)?,
+          },
+          _ => return Err(invalid("plugins", alias, "expected an `id`")),
+        },
+        _ => return Err(invalid("plugins", alias, "expected a string or a table")),
+      };
+      catalog.plugins.insert(alias.clone(), plugin);
+    }
+
+    Ok(catalog)
+  }
+
+  /// Version of a library or plugin table, either declared or referenced from the `versions` section.
+  fn version_of(&self, table: &toml::Table, section: &str, alias: &str) -> std::io::Result<Option<String>> {
+    let reference = table
+      .get("version")
+      .and_then(|v| v.as_table())
+      .and_then(|v| v.get("ref"))
+      .and_then(|r| r.as_str());
+
+    match (reference, table.get("version")) {
+      (Some(reference), _) => match self.versions.get(reference) {
+        Some(version) => Ok(Some(version.clone())),
+        None => Err(invalid(
+          section,
+          alias,
+          &format!("refers to the undefined version `{}`", reference),
+        )),
+      },
+      (None, Some(version)) => Ok(declared_version(version)),
+      (None, None) => Ok(None),
+    }
+  }
+
+  /// Entries of a section as comparable `(module, version)` pairs.
+  fn entries(&self, section: CatalogSection) -> BTreeMap<&str, (String, Option<String>)> {
+    match section {
+      CatalogSection::Versions => self
+        .versions
+        .iter()
+        .map(|(alias, version)| (alias.as_str(), (String::new(), Some(version.clone()))))
+        .collect(),
+      CatalogSection::Libraries => module_entries(&self.libraries),
+      CatalogSection::Bundles => self
+        .bundles
+        .iter()
+        .map(|(alias, libraries)| (alias.as_str(), (libraries.join(", "), None)))
+        .collect(),
+      CatalogSection::Plugins => module_entries(&self.plugins),
+    }
+  }
+}
+
+fn module_entries(modules: &BTreeMap<String, CatalogModule>) -> BTreeMap<&str, (String, Option<String>)> {
+  modules
+    .iter()
+    .map(|(alias, module)| (alias.as_str(), (module.module.clone(), module.version.clone())))
+    .collect()
+}
+
+/// Version of a `versions` entry or of a `version` key: a string, or the `strictly`, `require` or `prefer`
+/// version of a rich version.
+fn declared_version(value: &toml::Value) -> Option<String> {
+  match value {
+    toml::Value::String(version) => Some(version.clone()),
+    toml::Value::Table(t) => ["strictly", "require", "prefer"]
+      .iter()
+      .find_map(|k| t.get(*k).and_then(|v| v.as_str()))
+      .map(|v| v.to_string()),
+    _ => None,
+  }
+}
+
+fn invalid(section: &str, alias: &str, message: &str) -> std::io::Error {
+  std::io::Error::other(format!("{}.{}: {}", section, alias, message))
+}
+
+fn render_entry((module, version): &(String, Option<String>)) -> String {
+  match (module.is_empty(), version) {
+    (true, Some(version)) => version.clone(),
+    (false, Some(version)) => format!("{}:{}", module, version),
+    (_, None) => module.clone(),
+  }
+}
+
+/// Compares two revisions of a catalog, section by section and alias by alias.
+pub fn diff(before: &Catalog, after: &Catalog) -> Vec<CatalogChange> {
+  let mut changes: Vec<CatalogChange> = Vec::new();
+
+  for section in [
+    CatalogSection::Versions,
+    CatalogSection::Libraries,
+    CatalogSection::Bundles,
+    CatalogSection::Plugins,
+  ] {
+    let (entries_before, entries_after) = (before.entries(section), after.entries(section));
+    let aliases: BTreeSet<&str> = entries_before
+      .keys()
+      .chain(entries_after.keys())
+      .copied()
+      .collect();
+
+    for alias in aliases {
+      let (b, a) = (entries_before.get(alias), entries_after.get(alias));
+      let kind = match (b, a) {
+        (Some(b), Some(a)) if b == a => continue,
+        /* only versions of the same module are ordered */
+        (Some((module_b, Some(version_b))), Some((module_a, Some(version_a)))) if module_b == module_a => {
+          ChangeKind::of(version_b, version_a)
+        }
+        (Some(_), Some(_)) => ChangeKind::Changed,
+        (None, _) => ChangeKind::Added,
+        (_, None) => ChangeKind::Removed,
+      };
+
+      changes.push(CatalogChange {
+        section,
+        alias: alias.to_string(),
+        value_before: b.map_or(NOT_APPLICABLE.to_string(), render_entry),
+        value_after: a.map_or(NOT_APPLICABLE.to_string(), render_entry),
+        kind,
+      });
+    }
+  }
+
+  changes
+}
+
+/// Annotates the resolved dependencies declared in the catalog (and the markers of its plugins) with their alias
+/// and declared version, one annotation per resolved version. A resolved version differing from a declared one
+/// is a mismatch, unless the declared one is a range or a dynamic version.
+pub fn annotate(resolved: &[(String, Vec<TempList>)], catalog: &Catalog) -> Vec<CatalogAnnotation> {
+  let mut declared: BTreeMap<String, (CatalogSection, &str, &Option<String>)> = BTreeMap::new();
+  for (alias, library) in catalog.libraries.iter().rev() {
+    declared.insert(
+      library.module.clone(),
+      (CatalogSection::Libraries, alias, &library.version),
+    );
+  }
+  for (alias, plugin) in catalog.plugins.iter().rev() {
+    /* plugins resolve as their marker, `<id>:<id>.gradle.plugin` */
+    declared.insert(
+      format!("{}:{}.gradle.plugin", plugin.module, plugin.module),
+      (CatalogSection::Plugins, alias, &plugin.version),
+    );
+  }
+
+  let mut annotations: Vec<CatalogAnnotation> = Vec::new();
+
+  for (coordinate, temps) in resolved.iter() {
+    let (section, alias, declared_version) = match declared.get(coordinate) {
+      Some(d) => *d,
+      None => continue,
+    };
+
+    let mut by_version: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    for temp in temps.iter().filter(|t| t.version != NOT_APPLICABLE) {
+      by_version
+        .entry(&temp.version)
+        .or_default()
+        .push(temp.gradle_config_name.clone());
+    }
+
+    for (version, gradle_config_names) in by_version {
+      annotations.push(CatalogAnnotation {
+        coordinate: coordinate.clone(),
+        section,
+        alias: alias.to_string(),
+        declared_version: declared_version.clone(),
+        resolved_version: version.to_string(),
+        gradle_config_names,
+        mismatch: declared_version
+          .as_ref()
+          .is_some_and(|d| !d.contains(DYNAMIC_VERSION_CHARS) && !d.starts_with("latest.") && d != version),
+      });
+    }
+  }
+
+  annotations
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::temp;
+
+  const CATALOG: &str = r#"
+    [versions]
+    jackson = "2.15.2"
+    guava = { strictly = "32.1.2-jre" }
+
+    [libraries]
+    jackson-databind = { module = "com.fasterxml.jackson.core:jackson-databind", version.ref = "jackson" }
+    guava = { group = "com.google.guava", name = "guava", version.ref = "guava" }
+    snakeyaml = "org.yaml:snakeyaml:2.0"
+    spring-boot-starter = { module = "org.springframework.boot:spring-boot-starter" }
+
+    [bundles]
+    json = ["jackson-databind"]
+
+    [plugins]
+    spring-boot = { id = "org.springframework.boot", version = "3.1.3" }
+    spotless = "com.diffplug.spotless:6.22.0"
+  "#;
+
+  #[test]
+  fn it_parses_a_catalog_and_resolves_version_refs() {
+    let catalog = Catalog::parse(CATALOG).unwrap();
+
+    assert_eq!(catalog.versions["guava"], "32.1.2-jre");
+    assert_eq!(
+      catalog.libraries["jackson-databind"],
+      CatalogModule {
+        module: "com.fasterxml.jackson.core:jackson-databind".to_string(),
+        version: Some("2.15.2".to_string()),
+      }
+    );
+    assert_eq!(catalog.libraries["guava"].module, "com.google.guava:guava");
+    assert_eq!(catalog.libraries["snakeyaml"].version.as_deref(), Some("2.0"));
+    assert_eq!(catalog.libraries["spring-boot-starter"].version, None);
+    assert_eq!(catalog.bundles["json"], vec!["jackson-databind"]);
+    assert_eq!(catalog.plugins["spotless"].module, "com.diffplug.spotless");
+
+    let err = Catalog::parse("[libraries]\nguava = { module = \"a:b\", version.ref = \"missing\" }\n").unwrap_err();
+    assert_eq!(
+      err.to_string(),
+      "libraries.guava: refers to the undefined version `missing`"
+    );
+  }
+
+  #[test]
+  fn it_diffs_two_revisions_of_a_catalog() {
+    let before = Catalog::parse(CATALOG).unwrap();
+    let after = Catalog::parse(
+      &CATALOG
+        .replace("jackson = \"2.15.2\"", "jackson = \"2.15.3\"")
+        .replace(
+          "json = [\"jackson-databind\"]",
+          "json = [\"jackson-databind\", \"snakeyaml\"]",
+        )
+        .replace("    spotless = \"com.diffplug.spotless:6.22.0\"\n", ""),
+    )
+    .unwrap();
+
+    let changes = diff(&before, &after);
+
+    assert_eq!(
+      changes
+        .iter()
+        .map(|c| (c.section, c.alias.as_str(), c.kind))
+        .collect::<Vec<_>>(),
+      vec![
+        (CatalogSection::Versions, "jackson", ChangeKind::Upgraded),
+        (CatalogSection::Libraries, "jackson-databind", ChangeKind::Upgraded),
+        (CatalogSection::Bundles, "json", ChangeKind::Changed),
+        (CatalogSection::Plugins, "spotless", ChangeKind::Removed),
+      ]
+    );
+    assert_eq!(
+      changes[1].value_after,
+      "com.fasterxml.jackson.core:jackson-databind:2.15.3"
+    );
+    assert_eq!(changes[3].value_after, NOT_APPLICABLE);
+  }
+
+  #[test]
+  fn it_annotates_resolved_dependencies_and_flags_mismatches() {
+    let resolved = vec![
+      (
+        "com.fasterxml.jackson.core:jackson-databind".to_string(),
+        vec![
+          temp("compileClasspath", "2.15.3"),
+          temp("implementation", "2.15.2"),
+          temp("runtimeClasspath", "2.15.3"),
+        ],
+      ),
+      (
+        "com.google.guava:failureaccess".to_string(),
+        vec![temp("runtimeClasspath", "1.0.1")],
+      ),
+      (
+        "org.springframework.boot:org.springframework.boot.gradle.plugin".to_string(),
+        vec![temp("buildscript.classpath", "3.1.3")],
+      ),
+      (
+        "org.springframework.boot:spring-boot-starter".to_string(),
+        vec![temp("runtimeClasspath", "3.1.4")],
+      ),
+    ];
+
+    let annotations = annotate(&resolved, &Catalog::parse(CATALOG).unwrap());
+
+    assert_eq!(annotations.len(), 4);
+    assert_eq!(annotations[0].alias, "jackson-databind");
+    assert_eq!(annotations[0].resolved_version, "2.15.2");
+    assert!(!annotations[0].mismatch);
+    assert_eq!(
+      annotations[1].gradle_config_names,
+      vec!["compileClasspath", "runtimeClasspath"]
+    );
+    assert_eq!(annotations[1].declared_version.as_deref(), Some("2.15.2"));
+    assert!(annotations[1].mismatch);
+    assert_eq!(
+      (annotations[2].section, annotations[2].alias.as_str()),
+      (CatalogSection::Plugins, "spring-boot")
+    );
+    assert!(!annotations[2].mismatch);
+    assert!(!annotations[3].mismatch);
+  }
+}
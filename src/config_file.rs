@@ -0,0 +1,30 @@
+use serde::de::DeserializeOwned;
+use std::fs;
+use std::path::Path;
+
+/// Reads a configuration file (policy, acknowledgements), as YAML for `.yaml`/`.yml` extensions and as TOML otherwise.
+pub fn read_config_file<T, P>(path: P) -> std::io::Result<T>
+where
+  T: DeserializeOwned,
+  P: AsRef<Path>,
+{
+  let content = fs::read_to_string(&path)?;
+
+  let is_yaml = path
+    .as_ref()
+    .extension()
+    .is_some_and(|ext| ext == "yaml" || ext == "yml");
+
+  parse_config(&content, is_yaml)
+}
+
+/// Parses the content of a configuration file, as YAML or TOML.
+pub fn parse_config<T>(content: &str, is_yaml: bool) -> std::io::Result<T>
+where
+  T: DeserializeOwned,
+{
+  match is_yaml {
+    true => serde_yaml::from_str(content).map_err(std::io::Error::other),
+    false => toml::from_str(content).map_err(std::io::Error::other),
+  }
+}
@@ -0,0 +1,343 @@
+use rustc_hash::FxHashMap;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::parser::aliased;
+use crate::types::{Dependency, DependencyTree, GradleList, ProcessedDependencyObject, NOT_APPLICABLE};
+
+/// Default minimum Jaccard similarity of the dependency sets of a removed and an added configuration
+/// for them to be reported as a rename.
+pub const DEFAULT_RENAME_SIMILARITY: f64 = 0.8;
+
+/// Minimum number of dependencies of both configurations of a rename,
+/// as a couple of shared dependencies make any two small configurations look alike.
+const RENAME_MIN_DEPENDENCIES: usize = 3;
+
+/// A whole configuration appearing, disappearing or being renamed between before and after.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "event", rename_all = "lowercase")]
+pub enum ConfigurationEvent {
+  Added {
+    gradle_config_name: String,
+    dependencies: usize,
+  },
+  Removed {
+    gradle_config_name: String,
+    dependencies: usize,
+  },
+  Renamed {
+    from: String,
+    to: String,
+    /// Jaccard similarity of the `group:artifact` sets of both configurations, from 0 to 1 (rounded to 0.01).
+    similarity: f64,
+  },
+}
+
+/// `group:artifact` set of each configuration of one side, including the configurations
+/// without dependencies that are only known from the dependency trees (already aliased by the parser). \
+/// Aliased configurations are merged into the configuration they are aliased to.
+pub fn configuration_sets(
+  dep_map: &FxHashMap<String, Dependency>,
+  trees: &[DependencyTree],
+  aliases: &FxHashMap<String, String>,
+) -> BTreeMap<String, BTreeSet<String>> {
+  let mut sets: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+  for tree in trees.iter() {
+    sets
+      .entry(tree.gradle_config_name.clone())
+      .or_default();
+  }
+
+  for dep in dep_map.values() {
+    for entry in dep.gradle_entries.iter() {
+      sets
+        .entry(aliased(&entry.gradle_config_name, aliases).to_string())
+        .or_default()
+        .insert(format!("{}:{}", dep.namespace, dep.name));
+    }
+  }
+
+  sets
+}
+
+/// Detects the configurations only present on one side. \
+/// A removed and an added configuration with at least `RENAME_MIN_DEPENDENCIES` dependencies each and
+/// a similarity of at least `min_similarity` are paired as a rename, the most similar pairs first.
+pub fn detect(
+  before: &BTreeMap<String, BTreeSet<String>>,
+  after: &BTreeMap<String, BTreeSet<String>>,
+  min_similarity: f64,
+) -> Vec<ConfigurationEvent> {
+  let mut removed: Vec<&String> = before
+    .keys()
+    .filter(|k| !after.contains_key(*k))
+    .collect();
+  let mut added: Vec<&String> = after
+    .keys()
+    .filter(|k| !before.contains_key(*k))
+    .collect();
+
+  let mut candidates: Vec<(f64, &String, &String)> = removed
+    .iter()
+    .filter(|from| before[**from].len() >= RENAME_MIN_DEPENDENCIES)
+    .flat_map(|from| {
+      added
+        .iter()
+        .filter(|to| after[**to].len() >= RENAME_MIN_DEPENDENCIES)
+        .map(move |to| (jaccard(&before[*from], &after[*to]), *from, *to))
+    })
+    .filter(|(similarity, _, _)| *similarity >= min_similarity)
+    .collect();
+  candidates.sort_by(|a, b| {
+    b.0
+      .total_cmp(&a.0)
+      .then_with(|| (a.1, a.2).cmp(&(b.1, b.2)))
+  });
+
+  let mut events: Vec<ConfigurationEvent> = Vec::new();
+
+  for (similarity, from, to) in candidates {
+    if !removed.contains(&from) || !added.contains(&to) {
+      continue;
+    }
+
+    removed.retain(|r| *r != from);
+    added.retain(|a| *a != to);
+    events.push(ConfigurationEvent::Renamed {
+      from: from.clone(),
+      to: to.clone(),
+      similarity: (similarity * 100.0).round() / 100.0,
+    });
+  }
+
+  events.extend(
+    added
+      .into_iter()
+      .map(|name| ConfigurationEvent::Added {
+        gradle_config_name: name.clone(),
+        dependencies: after[name].len(),
+      }),
+  );
+  events.extend(
+    removed
+      .into_iter()
+      .map(|name| ConfigurationEvent::Removed {
+        gradle_config_name: name.clone(),
+        dependencies: before[name].len(),
+      }),
+  );
+
+  events
+}
+
+/// Folds the changes of the configurations behind `events` into them, so that they aren't reported twice. \
+/// The lists of a renamed configuration are merged into one list per dependency, comparing the version before
+/// the rename with the version after it, while the lists of added and removed configurations are dropped.
+/// Dependencies left without any list are dropped too.
+pub fn fold(values: &mut Vec<ProcessedDependencyObject>, events: &[ConfigurationEvent]) {
+  for value in values.iter_mut() {
+    for event in events.iter() {
+      match event {
+        ConfigurationEvent::Renamed { from, to, .. } => fold_rename(value, from, to),
+        ConfigurationEvent::Added { gradle_config_name, .. }
+        | ConfigurationEvent::Removed { gradle_config_name, .. } => {
+          value
+            .gradle_versions
+            .retain(|l| &l.gradle_config_name != gradle_config_name);
+          value
+            .dependency
+            .gradle_entries_before
+            .retain(|e| &e.gradle_config_name != gradle_config_name);
+          value
+            .dependency
+            .gradle_entries_after
+            .retain(|e| &e.gradle_config_name != gradle_config_name);
+        }
+      }
+    }
+
+    value.changed = value
+      .gradle_versions
+      .iter()
+      .any(|l| l.change_kind().is_change());
+  }
+
+  values.retain(|v| !v.gradle_versions.is_empty());
+}
+
+fn fold_rename(value: &mut ProcessedDependencyObject, from: &str, to: &str) {
+  let mut take = |name: &str| {
+    let idx = value
+      .gradle_versions
+      .iter()
+      .position(|l| l.gradle_config_name == name)?;
+    Some(value.gradle_versions.remove(idx))
+  };
+  let before = take(from);
+  let after = take(to);
+
+  if before.is_some() || after.is_some() {
+    value.gradle_versions.push(GradleList::new(
+      to,
+      before
+        .as_ref()
+        .map_or(NOT_APPLICABLE, |l| &l.version_before),
+      after
+        .as_ref()
+        .map_or(NOT_APPLICABLE, |l| &l.version_after),
+    ));
+  }
+
+  for entry in value.dependency.gradle_entries_before.iter_mut() {
+    if entry.gradle_config_name == from {
+      entry.gradle_config_name = to.to_string();
+    }
+  }
+}
+
+fn jaccard(a: &BTreeSet<String>, b: &BTreeSet<String>) -> f64 {
+  let union = a.union(b).count();
+
+  match union {
+    /* two empty configurations tell nothing about each other */
+    0 => 0.0,
+    _ => a.intersection(b).count() as f64 / union as f64,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::summary;
+  use crate::types::test_support::dep;
+
+  fn sets(configurations: Vec<(&str, Vec<&str>)>) -> BTreeMap<String, BTreeSet<String>> {
+    configurations
+      .into_iter()
+      .map(|(name, deps)| (name.to_string(), deps.into_iter().map(|d| d.to_string()).collect()))
+      .collect()
+  }
+
+  #[test]
+  fn it_detects_added_removed_and_renamed_configurations() {
+    let before = sets(vec![
+      ("compileClasspath", vec!["a:a"]),
+      ("productionRuntimeClasspath", vec!["a:a", "b:b", "c:c", "d:d", "e:e"]),
+      ("kapt", vec!["x:x"]),
+    ]);
+    let after = sets(vec![
+      ("compileClasspath", vec!["a:a"]),
+      ("runtimeClasspath", vec!["a:a", "b:b", "c:c", "d:d", "e:e", "f:f"]),
+      ("testFixturesRuntimeClasspath", vec!["f:f", "g:g"]),
+    ]);
+
+    let events = detect(&before, &after, DEFAULT_RENAME_SIMILARITY);
+
+    assert_eq!(
+      events,
+      vec![
+        ConfigurationEvent::Renamed {
+          from: "productionRuntimeClasspath".to_string(),
+          to: "runtimeClasspath".to_string(),
+          similarity: 0.83,
+        },
+        ConfigurationEvent::Added {
+          gradle_config_name: "testFixturesRuntimeClasspath".to_string(),
+          dependencies: 2,
+        },
+        ConfigurationEvent::Removed {
+          gradle_config_name: "kapt".to_string(),
+          dependencies: 1,
+        },
+      ]
+    );
+  }
+
+  #[test]
+  fn it_pairs_the_most_similar_configurations_first() {
+    let before = sets(vec![("a", vec!["1", "2", "3"]), ("b", vec!["1", "2", "4"])]);
+    let after = sets(vec![("c", vec!["1", "2", "4"])]);
+
+    let events = detect(&before, &after, DEFAULT_RENAME_SIMILARITY);
+
+    assert!(
+      matches!(&events[0], ConfigurationEvent::Renamed { from, similarity, .. } if from == "b" && *similarity == 1.0)
+    );
+    assert!(matches!(&events[1], ConfigurationEvent::Removed { gradle_config_name, .. } if gradle_config_name == "a"));
+  }
+
+  #[test]
+  fn it_only_pairs_configurations_similar_and_large_enough() {
+    let before = sets(vec![
+      ("kapt", vec!["x:x", "y:y"]),
+      ("productionRuntimeClasspath", vec!["a:a", "b:b", "c:c", "d:d"]),
+    ]);
+    let after = sets(vec![
+      ("kaptMain", vec!["x:x", "y:y"]),
+      ("runtimeClasspath", vec!["a:a", "b:b", "c:c", "e:e"]),
+    ]);
+
+    let renamed = |events: Vec<ConfigurationEvent>| {
+      events
+        .into_iter()
+        .filter(|e| matches!(e, ConfigurationEvent::Renamed { .. }))
+        .count()
+    };
+
+    assert_eq!(renamed(detect(&before, &after, DEFAULT_RENAME_SIMILARITY)), 0);
+    assert_eq!(renamed(detect(&before, &after, 0.6)), 1);
+  }
+
+  #[test]
+  fn it_folds_the_changes_of_renamed_added_and_removed_configurations() {
+    let mut values = vec![
+      dep(
+        "org.yaml:snakeyaml",
+        vec![
+          ("productionRuntimeClasspath", "1.33", "N/A"),
+          ("runtimeClasspath", "N/A", "2.0"),
+          ("compileClasspath", "1.33", "2.0"),
+        ],
+      ),
+      dep(
+        "com.google.guava:guava",
+        vec![
+          ("productionRuntimeClasspath", "31.1-jre", "N/A"),
+          ("runtimeClasspath", "N/A", "31.1-jre"),
+        ],
+      ),
+      dep("org.projectlombok:lombok", vec![("kapt", "1.18.30", "N/A")]),
+    ];
+    let events = vec![
+      ConfigurationEvent::Renamed {
+        from: "productionRuntimeClasspath".to_string(),
+        to: "runtimeClasspath".to_string(),
+        similarity: 1.0,
+      },
+      ConfigurationEvent::Removed {
+        gradle_config_name: "kapt".to_string(),
+        dependencies: 1,
+      },
+    ];
+
+    fold(&mut values, &events);
+
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[0].gradle_versions.len(), 2);
+    let runtime = &values[0].gradle_versions[1];
+    assert_eq!(runtime.gradle_config_name, "runtimeClasspath");
+    assert_eq!(
+      (runtime.version_before.as_str(), runtime.version_after.as_str()),
+      ("1.33", "2.0")
+    );
+    assert!(!values[1].changed);
+
+    let summary = summary::summarize(&values);
+    assert_eq!(summary.dependencies, 2);
+    assert_eq!(summary.changed_dependencies, 1);
+    assert_eq!(summary.configurations, 2);
+    assert_eq!((summary.added, summary.removed, summary.upgraded), (0, 0, 2));
+  }
+}
@@ -0,0 +1,179 @@
+use serde::Serialize;
+
+use crate::projects;
+use crate::types::TempList;
+
+/// Exit code of `--consistency` when inconsistencies are found, distinct from the `--fail-on` and `--check` ones.
+pub const INCONSISTENCY_EXIT_CODE: i32 = 10;
+
+/// Configuration pairs checked when none are given: compile vs runtime, and main vs test.
+pub const DEFAULT_PAIRS: [(&str, &str); 5] = [
+  ("compileClasspath", "runtimeClasspath"),
+  ("*CompileClasspath", "*RuntimeClasspath"),
+  ("compileClasspath", "testCompileClasspath"),
+  ("runtimeClasspath", "testRuntimeClasspath"),
+  ("*RuntimeClasspath", "*UnitTestRuntimeClasspath"),
+];
+
+/// A dependency resolving to different versions in two related configurations of one snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Inconsistency {
+  pub coordinate: String,
+  pub configuration: String,
+  pub version: String,
+  pub other_configuration: String,
+  pub other_version: String,
+}
+
+/// Expands the pairs into the pairs of configurations present in the snapshot, within each project. \
+/// A `*` in the first name of a pair matches any part of a configuration name, and is replaced
+/// by that part in the second name, e.g. `*CompileClasspath=*RuntimeClasspath` pairs
+/// `debugCompileClasspath` with `debugRuntimeClasspath`.
+pub fn expand_pairs(pairs: &[(String, String)], configurations: &[String]) -> Vec<(String, String)> {
+  let mut res: Vec<(String, String)> = Vec::new();
+
+  for (first, second) in pairs.iter() {
+    for configuration in configurations.iter() {
+      /* names of subprojects are qualified by the project path, and only paired within it */
+      let (project, name) = match configuration.starts_with(':') {
+        true => projects::split_configuration(configuration),
+        false => ("", configuration.as_str()),
+      };
+
+      let other = match first.split_once('*') {
+        Some((prefix, suffix)) => match name
+          .strip_prefix(prefix)
+          .and_then(|c| c.strip_suffix(suffix))
+        {
+          Some(part) => second.replacen('*', part, 1),
+          None => continue,
+        },
+        None if first == name => second.clone(),
+        None => continue,
+      };
+
+      let other = match project.is_empty() {
+        true => other,
+        false => format!("{}:{}", project, other),
+      };
+
+      let pair = (configuration.clone(), other);
+      if pair.0 != pair.1 && configurations.contains(&pair.1) && !res.contains(&pair) {
+        res.push(pair);
+      }
+    }
+  }
+
+  res
+}
+
+/// Compares the resolved version of each dependency in both configurations of each pair. \
+/// Dependencies only present in one of the configurations are not inconsistencies.
+pub fn check(resolved: &[(String, Vec<TempList>)], pairs: &[(String, String)]) -> Vec<Inconsistency> {
+  let mut inconsistencies: Vec<Inconsistency> = Vec::new();
+
+  for (coordinate, versions) in resolved.iter() {
+    let version_in = |configuration: &str| {
+      versions
+        .iter()
+        .find(|t| t.gradle_config_name == configuration)
+        .map(|t| &t.version)
+    };
+
+    for (first, second) in pairs.iter() {
+      if let (Some(version), Some(other_version)) = (version_in(first), version_in(second)) {
+        if version != other_version {
+          inconsistencies.push(Inconsistency {
+            coordinate: coordinate.clone(),
+            configuration: first.clone(),
+            version: version.clone(),
+            other_configuration: second.clone(),
+            other_version: other_version.clone(),
+          });
+        }
+      }
+    }
+  }
+
+  inconsistencies
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::temp;
+
+  fn strings(names: &[&str]) -> Vec<String> {
+    names.iter().map(|n| n.to_string()).collect()
+  }
+
+  fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+      .iter()
+      .map(|(a, b)| (a.to_string(), b.to_string()))
+      .collect()
+  }
+
+  #[test]
+  fn it_expands_wildcard_pairs_to_present_configurations() {
+    let configurations = strings(&[
+      "compileClasspath",
+      "runtimeClasspath",
+      "debugCompileClasspath",
+      "debugRuntimeClasspath",
+      "releaseCompileClasspath",
+      ":service-a:compileClasspath",
+      ":service-a:runtimeClasspath",
+      ":service-b:compileClasspath",
+    ]);
+
+    let res = expand_pairs(
+      &pairs(&[
+        ("compileClasspath", "runtimeClasspath"),
+        ("*CompileClasspath", "*RuntimeClasspath"),
+        ("runtimeClasspath", "testRuntimeClasspath"),
+      ]),
+      &configurations,
+    );
+
+    assert_eq!(
+      res,
+      pairs(&[
+        ("compileClasspath", "runtimeClasspath"),
+        (":service-a:compileClasspath", ":service-a:runtimeClasspath"),
+        ("debugCompileClasspath", "debugRuntimeClasspath"),
+      ])
+    );
+  }
+
+  #[test]
+  fn it_flags_versions_differing_between_paired_configurations() {
+    let resolved = vec![
+      (
+        "com.fasterxml.jackson.core:jackson-databind".to_string(),
+        vec![temp("compileClasspath", "2.15.2"), temp("runtimeClasspath", "2.17.0")],
+      ),
+      (
+        "org.yaml:snakeyaml".to_string(),
+        vec![temp("compileClasspath", "2.0"), temp("runtimeClasspath", "2.0")],
+      ),
+      (
+        "org.slf4j:slf4j-api".to_string(),
+        vec![temp("runtimeClasspath", "2.0.9")],
+      ),
+    ];
+
+    let res = check(&resolved, &pairs(&[("compileClasspath", "runtimeClasspath")]));
+
+    assert_eq!(
+      res,
+      vec![Inconsistency {
+        coordinate: "com.fasterxml.jackson.core:jackson-databind".to_string(),
+        configuration: "compileClasspath".to_string(),
+        version: "2.15.2".to_string(),
+        other_configuration: "runtimeClasspath".to_string(),
+        other_version: "2.17.0".to_string(),
+      }]
+    );
+  }
+}
@@ -0,0 +1,230 @@
+use rustc_hash::FxHashMap;
+use schemars::{schema_for, JsonSchema};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+use crate::alignment::Misalignment;
+use crate::catalog::CatalogReport;
+use crate::configurations::ConfigurationEvent;
+use crate::ordering::{ChangeGroup, GroupBy};
+use crate::plugins::{self, PluginChange};
+use crate::summary::{self, DiffSummary};
+use crate::types::{Dependency, ProcessedDependencyObject};
+
+/// Version of the JSON output format, bumped on incompatible changes.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// The JSON output of a diff, which can be read back as a `-b`/`-a` input.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[schemars(title = "Gradle Deps Differ diff document")]
+pub struct DiffDocument {
+  /// Version of this format, currently `1`.
+  pub format_version: u32,
+  #[serde(default)]
+  pub summary: DiffSummary,
+  pub dependencies: Vec<ProcessedDependencyObject>,
+  /// Configurations added, removed or renamed as a whole.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub configuration_events: Vec<ConfigurationEvent>,
+  /// Changes of the plugins of the buildscript classpath (from a `buildEnvironment` output).
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub plugin_changes: Vec<PluginChange>,
+  /// Artifact families misaligned after but not before.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub newly_misaligned: Vec<Misalignment>,
+  /// Version catalog changes and the catalog aliases of the dependencies after, with `--catalog`.
+  #[serde(default, skip_serializing_if = "CatalogReport::is_empty")]
+  pub catalog: CatalogReport,
+  /// Key the `groups` are grouped by, with `--group-by`.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub group_by: Option<GroupBy>,
+  /// Changes of `dependencies` grouped by `group_by`.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub groups: Vec<ChangeGroup>,
+}
+
+/// Side of a diff document to read as an input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+  Before,
+  After,
+}
+
+impl DiffDocument {
+  pub fn new(dependencies: Vec<ProcessedDependencyObject>) -> Self {
+    Self {
+      format_version: FORMAT_VERSION,
+      summary: summary::summarize(&dependencies),
+      plugin_changes: plugins::changes(&dependencies),
+      dependencies,
+      configuration_events: Vec::new(),
+      newly_misaligned: Vec::new(),
+      catalog: CatalogReport::default(),
+      group_by: None,
+      groups: Vec::new(),
+    }
+  }
+
+  pub fn from_file<P>(path: P) -> std::io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    let content = fs::read_to_string(path)?;
+    let document: DiffDocument = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+
+    if document.format_version > FORMAT_VERSION {
+      return Err(std::io::Error::other(format!(
+        "unsupported format_version {} (supported up to {})",
+        document.format_version, FORMAT_VERSION
+      )));
+    }
+
+    Ok(document)
+  }
+
+  /// Rebuilds the dependency map of one side of the diff, keyed by `group:artifact` like the `DependencyParser` maps.
+  pub fn dep_map(&self, side: Side) -> FxHashMap<String, Dependency> {
+    let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+
+    for value in self.dependencies.iter() {
+      let entries = match side {
+        Side::Before => &value.dependency.gradle_entries_before,
+        Side::After => &value.dependency.gradle_entries_after,
+      };
+
+      /* the dependency doesn't exist on this side */
+      if entries.is_empty() {
+        continue;
+      }
+
+      dep_map.insert(
+        format!("{}:{}", value.dependency.namespace, value.dependency.name),
+        Dependency {
+          name: value.dependency.name.clone(),
+          namespace: value.dependency.namespace.clone(),
+          gradle_entries: entries.clone(),
+        },
+      );
+    }
+
+    dep_map
+  }
+}
+
+/// Whether the file is a diff document rather than a Gradle output, from its first non-blank character.
+pub fn is_document<P>(path: P) -> bool
+where
+  P: AsRef<Path>,
+{
+  fs::read_to_string(path).is_ok_and(|content| content.trim_start().starts_with('{'))
+}
+
+/// JSON Schema of `DiffDocument`, as published in `schema/diff-document.schema.json`.
+pub fn schema() -> String {
+  let mut schema = serde_json::to_string_pretty(&schema_for!(DiffDocument)).unwrap();
+  schema.push('\n');
+  schema
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::parser::DependencyParser;
+  use crate::types::{GradleEntry, GradleList, ParsedDependency, ProcessedDependency, Version, Versions};
+
+  fn entry(config: &str, transitive: Vec<Version>, pinned: Version) -> GradleEntry {
+    GradleEntry {
+      gradle_config_name: config.to_string(),
+      versions: Versions { transitive, pinned },
+    }
+  }
+
+  fn document() -> DiffDocument {
+    DiffDocument::new(vec![ProcessedDependencyObject {
+      dependency: ProcessedDependency {
+        name: "snakeyaml".to_string(),
+        namespace: "org.yaml".to_string(),
+        gradle_entries_before: vec![entry(
+          "runtimeClasspath",
+          vec![Version::NotApplicable],
+          Version::Pinned("1.33".to_string()),
+        )],
+        gradle_entries_after: Vec::new(),
+      },
+      changed: true,
+      gradle_versions: vec![GradleList::new("runtimeClasspath", "1.33", "N/A")],
+    }])
+  }
+
+  #[test]
+  fn it_round_trips_versions_losslessly() {
+    let json = serde_json::to_string(&document()).unwrap();
+
+    assert!(json.contains(r#""transitive":[null],"pinned":"1.33""#));
+    assert!(json.contains(r#""version_before":"1.33","version_after":null"#));
+
+    let read: DiffDocument = serde_json::from_str(&json).unwrap();
+    let versions = &read.dependencies[0].dependency.gradle_entries_before[0].versions;
+
+    assert_eq!(versions.transitive, vec![Version::NotApplicable]);
+    assert_eq!(versions.pinned, Version::Pinned("1.33".to_string()));
+    assert_eq!(read.dependencies[0].gradle_versions[0].version_after, "N/A");
+  }
+
+  #[test]
+  fn it_reads_each_side_as_a_dep_map() {
+    let document = document();
+
+    let before = document.dep_map(Side::Before);
+    assert_eq!(before.get("org.yaml:snakeyaml").unwrap().namespace, "org.yaml");
+    assert!(document.dep_map(Side::After).is_empty());
+  }
+
+  #[test]
+  fn it_round_trips_artifacts_of_the_same_name_in_different_groups() {
+    let mut parser = DependencyParser::new();
+    for version in ["1.0.0", "1.1.0"] {
+      parser.start_configuration("compileClasspath");
+      for namespace in ["com.example", "org.jetbrains"] {
+        parser.add_dependency(
+          0,
+          false,
+          ParsedDependency {
+            name: "annotations".to_string(),
+            namespace: namespace.to_string(),
+            transitive: Version::Transitive(version.to_string()),
+            pinned: Version::NotApplicable,
+          },
+        );
+      }
+      parser.end_input();
+    }
+
+    let json = serde_json::to_string(&DiffDocument::new(parser.compare_versions())).unwrap();
+    let read: DiffDocument = serde_json::from_str(&json).unwrap();
+
+    let mut restored = DependencyParser::new();
+    restored.load_dep_map(read.dep_map(Side::Before));
+    restored.load_dep_map(read.dep_map(Side::After));
+
+    let mut coordinates: Vec<String> = restored
+      .compare_versions()
+      .iter()
+      .filter(|d| d.changed)
+      .map(|d| format!("{}:{}", d.dependency.namespace, d.dependency.name))
+      .collect();
+    coordinates.sort();
+
+    assert_eq!(
+      coordinates,
+      vec!["com.example:annotations", "org.jetbrains:annotations"]
+    );
+  }
+
+  #[test]
+  fn it_matches_the_published_schema() {
+    /* regenerate with `gradle-deps-differ --print-schema > schema/diff-document.schema.json` */
+    assert_eq!(schema(), include_str!("../schema/diff-document.schema.json"));
+  }
+}
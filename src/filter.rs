@@ -0,0 +1,225 @@
+use rustc_hash::FxHashMap;
+
+use crate::glob;
+use crate::parser::aliased;
+use crate::types::{ChangeKind, DependencyTree, GradleList, ProcessedDependencyObject, TreeNode};
+
+/// Narrows the diff down to the configurations, dependencies and changes of interest. \
+/// Empty include lists include everything, and excludes take precedence over includes.
+#[derive(Debug, Clone, Default)]
+pub struct Filter {
+  /// Configuration globs.
+  pub include_configurations: Vec<String>,
+  pub exclude_configurations: Vec<String>,
+  /// `group:artifact` globs.
+  pub include_dependencies: Vec<String>,
+  pub exclude_dependencies: Vec<String>,
+  pub only_changed: bool,
+  pub change_kinds: Vec<ChangeKind>,
+}
+
+impl Filter {
+  pub fn matches_configuration(&self, name: &str) -> bool {
+    included(&self.include_configurations, &self.exclude_configurations, name)
+  }
+
+  pub fn matches_dependency(&self, coordinate: &str) -> bool {
+    included(&self.include_dependencies, &self.exclude_dependencies, coordinate)
+  }
+
+  fn matches_list(&self, list: &GradleList) -> bool {
+    let kind = list.change_kind();
+
+    self.matches_configuration(&list.gradle_config_name)
+      && (!self.only_changed || kind.is_change())
+      && (self.change_kinds.is_empty() || self.change_kinds.contains(&kind))
+  }
+
+  /// Drops the configurations of each dependency that don't match, then the dependencies
+  /// left without any configuration. \
+  /// Configurations are matched by the name they are aliased to in `aliases`, if any, as in the changes.
+  pub fn apply(
+    &self,
+    values: Vec<ProcessedDependencyObject>,
+    aliases: &FxHashMap<String, String>,
+  ) -> Vec<ProcessedDependencyObject> {
+    values
+      .into_iter()
+      .filter(|v| self.matches_dependency(&format!("{}:{}", v.dependency.namespace, v.dependency.name)))
+      .filter_map(|mut v| {
+        v.gradle_versions.retain(|l| self.matches_list(l));
+        if v.gradle_versions.is_empty() {
+          return None;
+        }
+
+        v.dependency
+          .gradle_entries_before
+          .retain(|e| self.matches_configuration(aliased(&e.gradle_config_name, aliases)));
+        v.dependency
+          .gradle_entries_after
+          .retain(|e| self.matches_configuration(aliased(&e.gradle_config_name, aliases)));
+        v.changed = v
+          .gradle_versions
+          .iter()
+          .any(|l| l.change_kind().is_change());
+
+        Some(v)
+      })
+      .collect()
+  }
+
+  /// Drops the trees of the configurations that don't match, then the nodes of the dependencies that don't match,
+  /// unless a matching one is below them.
+  pub fn apply_to_trees(&self, trees: &mut Vec<DependencyTree>) {
+    trees.retain(|t| self.matches_configuration(&t.gradle_config_name));
+
+    if self.include_dependencies.is_empty() && self.exclude_dependencies.is_empty() {
+      return;
+    }
+    for tree in trees.iter_mut() {
+      self.prune(&mut tree.roots);
+    }
+  }
+
+  fn prune(&self, nodes: &mut Vec<TreeNode>) {
+    nodes.retain_mut(|node| {
+      self.prune(&mut node.children);
+      !node.children.is_empty() || self.matches_dependency(&node.coordinate())
+    });
+  }
+}
+
+fn included(include: &[String], exclude: &[String], text: &str) -> bool {
+  (include.is_empty() || glob::matches_any(include, text)) && !glob::matches_any(exclude, text)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::{dep, node};
+  use crate::types::{GradleEntry, Version, Versions};
+
+  fn values() -> Vec<ProcessedDependencyObject> {
+    vec![
+      dep(
+        "org.yaml:snakeyaml",
+        vec![
+          ("debugRuntimeClasspath", "1.33", "2.0"),
+          ("releaseRuntimeClasspath", "1.33", "1.33"),
+          ("testRuntimeClasspath", "N/A", "2.0"),
+        ],
+      ),
+      dep(
+        "com.google.guava:guava",
+        vec![("debugRuntimeClasspath", "31.1-jre", "31.1-jre")],
+      ),
+    ]
+  }
+
+  #[test]
+  fn it_filters_configurations_and_dependencies_by_glob() {
+    let filter = Filter {
+      include_configurations: vec!["*RuntimeClasspath".to_string()],
+      exclude_configurations: vec!["test*".to_string()],
+      exclude_dependencies: vec!["com.google.*".to_string()],
+      ..Default::default()
+    };
+
+    let res = filter.apply(values(), &FxHashMap::default());
+
+    assert_eq!(res.len(), 1);
+    let configs: Vec<&str> = res[0]
+      .gradle_versions
+      .iter()
+      .map(|l| l.gradle_config_name.as_str())
+      .collect();
+    assert_eq!(configs, vec!["debugRuntimeClasspath", "releaseRuntimeClasspath"]);
+  }
+
+  #[test]
+  fn it_prunes_trees_to_the_matching_dependencies_and_their_ancestors() {
+    let mut trees = vec![DependencyTree {
+      gradle_config_name: "runtimeClasspath".to_string(),
+      roots: vec![
+        node(
+          "org.springframework.boot:spring-boot-starter",
+          "1.0",
+          vec![
+            node("org.yaml:snakeyaml", "1.0", Vec::new()),
+            node("org.springframework:spring-core", "1.0", Vec::new()),
+          ],
+        ),
+        node("com.google.guava:guava", "1.0", Vec::new()),
+        node("org.yaml:snakeyaml-engine", "1.0", Vec::new()),
+      ],
+    }];
+
+    let filter = Filter {
+      include_dependencies: vec!["org.yaml:*".to_string()],
+      exclude_dependencies: vec!["*:snakeyaml-engine".to_string()],
+      ..Default::default()
+    };
+    filter.apply_to_trees(&mut trees);
+
+    let roots = &trees[0].roots;
+    assert_eq!(roots.len(), 1);
+    assert_eq!(roots[0].name, "spring-boot-starter");
+    let children: Vec<&str> = roots[0]
+      .children
+      .iter()
+      .map(|c| c.name.as_str())
+      .collect();
+    assert_eq!(children, vec!["snakeyaml"]);
+  }
+
+  #[test]
+  fn it_keeps_only_changes_of_the_given_kinds() {
+    let only_changed = Filter {
+      only_changed: true,
+      ..Default::default()
+    };
+
+    let res = only_changed.apply(values(), &FxHashMap::default());
+    assert_eq!(res.len(), 1);
+    assert_eq!(res[0].gradle_versions.len(), 2);
+
+    let added = Filter {
+      change_kinds: vec![ChangeKind::Added],
+      ..Default::default()
+    };
+
+    let res = added.apply(values(), &FxHashMap::default());
+    assert_eq!(res[0].gradle_versions.len(), 1);
+    assert_eq!(res[0].gradle_versions[0].gradle_config_name, "testRuntimeClasspath");
+  }
+
+  #[test]
+  fn it_filters_the_entries_by_their_aliased_configuration() {
+    let entry = |name: &str| GradleEntry {
+      gradle_config_name: name.to_string(),
+      versions: Versions {
+        transitive: vec![Version::Transitive("1.33".to_string())],
+        pinned: Version::default(),
+      },
+    };
+    let mut value = dep("org.yaml:snakeyaml", vec![("runtimeClasspath", "1.33", "2.0")]);
+    value.dependency.gradle_entries_before = vec![entry("productionRuntimeClasspath"), entry("testRuntimeClasspath")];
+
+    let mut aliases: FxHashMap<String, String> = FxHashMap::default();
+    aliases.insert("productionRuntimeClasspath".to_string(), "runtimeClasspath".to_string());
+    let filter = Filter {
+      include_configurations: vec!["runtimeClasspath".to_string()],
+      ..Default::default()
+    };
+
+    let res = filter.apply(vec![value], &aliases);
+
+    let entries: Vec<&str> = res[0]
+      .dependency
+      .gradle_entries_before
+      .iter()
+      .map(|e| e.gradle_config_name.as_str())
+      .collect();
+    assert_eq!(entries, vec!["productionRuntimeClasspath"]);
+  }
+}
@@ -0,0 +1,147 @@
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::glob;
+use crate::types::{ChangeKind, ProcessedDependencyObject, SemverLevel};
+
+/// Conditions that fail a CI build, each exiting with its own code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FailOn {
+  /// Any dependency version changed, or a dependency was added or removed
+  Change,
+  /// A dependency version was downgraded
+  Downgrade,
+  /// A dependency version changed major version
+  Major,
+  /// A dependency was added to a configuration
+  Added,
+  /// A dependency was removed from a configuration
+  Removed,
+  /// Anything changed in one of the `--fail-on-configuration` configurations
+  #[value(skip)]
+  Configuration,
+}
+
+impl FailOn {
+  /// Exit code of the condition, from 3 on so that it can't be mistaken for the one of command line usage errors (2).
+  pub fn exit_code(&self) -> i32 {
+    match self {
+      FailOn::Change => 3,
+      FailOn::Downgrade => 4,
+      FailOn::Major => 5,
+      FailOn::Added => 6,
+      FailOn::Removed => 7,
+      FailOn::Configuration => 8,
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+pub struct GateResult {
+  pub passed: bool,
+  /// Exit code of the first tripped condition, in the order they were given, or `0`.
+  pub exit_code: i32,
+  pub tripped: Vec<TrippedCondition>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TrippedCondition {
+  pub condition: FailOn,
+  pub exit_code: i32,
+  /// Number of (dependency, configuration) changes matching the condition.
+  pub count: usize,
+}
+
+/// Evaluates `conditions` against the diff. `configurations` are the glob patterns
+/// checked by `FailOn::Configuration`.
+pub fn evaluate(values: &[ProcessedDependencyObject], conditions: &[FailOn], configurations: &[String]) -> GateResult {
+  let tripped: Vec<TrippedCondition> = conditions
+    .iter()
+    .map(|condition| TrippedCondition {
+      condition: *condition,
+      exit_code: condition.exit_code(),
+      count: count_matching(values, *condition, configurations),
+    })
+    .filter(|t| t.count > 0)
+    .collect();
+
+  GateResult {
+    passed: tripped.is_empty(),
+    exit_code: tripped.first().map_or(0, |t| t.exit_code),
+    tripped,
+  }
+}
+
+fn count_matching(values: &[ProcessedDependencyObject], condition: FailOn, configurations: &[String]) -> usize {
+  values
+    .iter()
+    .flat_map(|v| v.gradle_versions.iter())
+    .filter(|list| !list.is_acknowledged())
+    .filter(|list| {
+      let kind = list.change_kind();
+
+      match condition {
+        FailOn::Change => kind.is_change(),
+        FailOn::Downgrade => kind == ChangeKind::Downgraded,
+        FailOn::Major => SemverLevel::of(&list.version_before, &list.version_after) == SemverLevel::Major,
+        FailOn::Added => kind == ChangeKind::Added,
+        FailOn::Removed => kind == ChangeKind::Removed,
+        FailOn::Configuration => kind.is_change() && glob::matches_any(configurations, &list.gradle_config_name),
+      }
+    })
+    .count()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::dep;
+  use crate::types::Acknowledged;
+
+  #[test]
+  fn it_trips_conditions_in_the_given_order() {
+    let values = vec![
+      dep("dep_ns:a", vec![("runtimeClasspath", "2.0.0", "1.9.0")]),
+      dep("dep_ns:b", vec![("testRuntimeClasspath", "1.0.0", "2.0.0")]),
+    ];
+
+    let res = evaluate(
+      &values,
+      &[FailOn::Added, FailOn::Major, FailOn::Downgrade, FailOn::Change],
+      &[],
+    );
+
+    assert!(!res.passed);
+    assert_eq!(res.exit_code, FailOn::Major.exit_code());
+    assert_eq!(res.tripped.len(), 3);
+    assert_eq!(res.tripped[2].condition, FailOn::Change);
+    assert_eq!(res.tripped[2].count, 2);
+  }
+
+  #[test]
+  fn it_ignores_acknowledged_changes() {
+    let mut values = vec![dep("dep_ns:a", vec![("runtimeClasspath", "2.0.0", "1.9.0")])];
+    values[0].gradle_versions[0].acknowledged = Some(Acknowledged {
+      reason: "Reviewed".to_string(),
+      expires: None,
+    });
+
+    assert!(evaluate(&values, &[FailOn::Change], &[]).passed);
+  }
+
+  #[test]
+  fn it_only_checks_matching_configurations() {
+    let values = vec![
+      dep("dep_ns:a", vec![("testRuntimeClasspath", "1.0.0", "1.1.0")]),
+      dep("dep_ns:b", vec![("runtimeClasspath", "1.0.0", "1.0.0")]),
+    ];
+
+    let res = evaluate(&values, &[FailOn::Configuration], &["runtime*".to_string()]);
+    assert!(res.passed);
+    assert_eq!(res.exit_code, 0);
+
+    let res = evaluate(&values, &[FailOn::Configuration], &["*RuntimeClasspath".to_string()]);
+    assert_eq!(res.exit_code, FailOn::Configuration.exit_code());
+  }
+}
@@ -0,0 +1,51 @@
+/// Matches `text` against a glob `pattern`, where `*` matches any sequence
+/// of characters (including none) and `?` matches exactly one character.
+pub fn matches(pattern: &str, text: &str) -> bool {
+  let pattern: Vec<char> = pattern.chars().collect();
+  let text: Vec<char> = text.chars().collect();
+
+  let (mut p, mut t) = (0, 0);
+  /* position of the last `*` in pattern and the text position it was tried at */
+  let mut backtrack: Option<(usize, usize)> = None;
+
+  while t < text.len() {
+    if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+      p += 1;
+      t += 1;
+    } else if p < pattern.len() && pattern[p] == '*' {
+      backtrack = Some((p, t));
+      p += 1;
+    } else if let Some((star_p, star_t)) = backtrack {
+      /* let the last `*` consume one more character */
+      p = star_p + 1;
+      t = star_t + 1;
+      backtrack = Some((star_p, star_t + 1));
+    } else {
+      return false;
+    }
+  }
+
+  pattern[p..].iter().all(|c| *c == '*')
+}
+
+/// Whether `text` matches any of `patterns`.
+pub fn matches_any(patterns: &[String], text: &str) -> bool {
+  patterns.iter().any(|p| matches(p, text))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn it_matches_wildcards() {
+    assert!(matches("org.springframework*", "org.springframework.boot:spring-boot"));
+    assert!(matches("*RuntimeClasspath", "testRuntimeClasspath"));
+    assert!(matches("*:jackson-*", "com.fasterxml.jackson.core:jackson-databind"));
+    assert!(matches("junit?", "junit5"));
+    assert!(matches("*", ""));
+    assert!(!matches("*RuntimeClasspath", "runtimeClasspath"));
+    assert!(!matches("com.sun*", "org.com.sun"));
+    assert!(!matches("junit?", "junit"));
+  }
+}
@@ -0,0 +1,73 @@
+use handlebars::{handlebars_helper, Handlebars};
+use version_compare::{compare_to, Cmp};
+
+use crate::types::{ChangeKind, SemverLevel};
+
+handlebars_helper!(change_kind: |before: str, after: str| ChangeKind::of(before, after).to_string());
+handlebars_helper!(semver_level: |before: str, after: str| SemverLevel::of(before, after).to_string());
+handlebars_helper!(format_coordinate: |group: str, artifact: str, {version: str = ""}| {
+  match version {
+    "" | "N/A" => format!("{}:{}", group, artifact),
+    _ => format!("{}:{}:{}", group, artifact, version),
+  }
+});
+handlebars_helper!(version_eq: |a: str, b: str| compare_to(a, b, Cmp::Eq).unwrap_or(a == b));
+handlebars_helper!(version_gt: |a: str, b: str| compare_to(a, b, Cmp::Gt).unwrap_or(false));
+handlebars_helper!(version_lt: |a: str, b: str| compare_to(a, b, Cmp::Lt).unwrap_or(false));
+
+/// Registers the helpers available to report templates, on top of the built-in
+/// `eq`, `ne`, `gt`, `gte`, `lt`, `lte`, `and`, `or`, `not` and `len`:
+/// * `{{change_kind before after}}`: `added`, `removed`, `upgraded`, `downgraded`, `changed` or `unchanged`
+/// * `{{semver_level before after}}`: `major`, `minor`, `patch`, `other` or `none`
+/// * `{{format_coordinate group artifact version=v}}`: `group:artifact[:version]`
+/// * `{{version_eq a b}}`, `{{version_gt a b}}`, `{{version_lt a b}}`: version-aware comparisons
+pub fn register(handlebars: &mut Handlebars) {
+  handlebars.register_helper("change_kind", Box::new(change_kind));
+  handlebars.register_helper("semver_level", Box::new(semver_level));
+  handlebars.register_helper("format_coordinate", Box::new(format_coordinate));
+  handlebars.register_helper("version_eq", Box::new(version_eq));
+  handlebars.register_helper("version_gt", Box::new(version_gt));
+  handlebars.register_helper("version_lt", Box::new(version_lt));
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn render(template: &str) -> String {
+    let mut handlebars = Handlebars::new();
+    register(&mut handlebars);
+    handlebars
+      .render_template(template, &json!({}))
+      .unwrap()
+  }
+
+  #[test]
+  fn it_renders_change_and_semver_helpers() {
+    assert_eq!(render(r#"{{change_kind "1.0.0" "N/A"}}"#), "removed");
+    assert_eq!(render(r#"{{semver_level "6.0.11" "6.1.0"}}"#), "minor");
+  }
+
+  #[test]
+  fn it_formats_coordinates() {
+    assert_eq!(
+      render(r#"{{format_coordinate "org.yaml" "snakeyaml"}}"#),
+      "org.yaml:snakeyaml"
+    );
+    assert_eq!(
+      render(r#"{{format_coordinate "org.yaml" "snakeyaml" version="2.0"}}"#),
+      "org.yaml:snakeyaml:2.0"
+    );
+  }
+
+  #[test]
+  fn it_compares_versions() {
+    assert_eq!(render(r#"{{#if (version_gt "1.10.0" "1.9.0")}}yes{{/if}}"#), "yes");
+    assert_eq!(
+      render(r#"{{#if (version_lt "1.10.0" "1.9.0")}}yes{{else}}no{{/if}}"#),
+      "no"
+    );
+    assert_eq!(render(r#"{{#if (version_eq "1.0" "1.0.0")}}yes{{/if}}"#), "yes");
+  }
+}
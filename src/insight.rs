@@ -0,0 +1,295 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+const ARROW: &str = " -> ";
+const SELECTION_REASONS: &str = "Selection reasons:";
+const INSIGHT_TASK: &str = ":dependencyInsight";
+
+/// Why a module was selected and by whom it was requested, from a `dependencyInsight` output.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Insight {
+  /// `group:artifact` of the module.
+  pub coordinate: String,
+  pub selected_version: String,
+  /// Selection reasons, e.g. `By conflict resolution : between versions 2.15.3 and 2.15.2`.
+  pub reasons: Vec<String>,
+  /// Direct requesters of the module with the version they requested, e.g.
+  /// `org.springframework.boot:spring-boot-starter-json:3.1.4 (2.15.2)`.
+  pub requested_by: Vec<String>,
+}
+
+/// Parses `dependencyInsight` outputs, one per input like the `DependencyParser`.
+pub struct InsightParser {
+  pub insights: [Vec<Insight>; 2],
+  pub active: usize,
+  in_reasons: bool,
+  /// Index in `insights[active]` and requested version of the module whose requesters are being parsed.
+  requested: Option<(usize, String)>,
+}
+
+/// Change of the selection of a module between before and after.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelectionChange {
+  pub coordinate: String,
+  pub version_before: Option<String>,
+  pub version_after: Option<String>,
+  pub reasons_before: Vec<String>,
+  pub reasons_after: Vec<String>,
+  pub requested_by_before: Vec<String>,
+  pub requested_by_after: Vec<String>,
+}
+
+impl InsightParser {
+  pub fn new() -> Self {
+    InsightParser {
+      insights: [Vec::new(), Vec::new()],
+      active: 0,
+      in_reasons: false,
+      requested: None,
+    }
+  }
+
+  pub fn parse_file(&mut self, file: BufReader<fs::File>) {
+    self.in_reasons = false;
+    self.requested = None;
+
+    for line in file.lines().map(|l| l.unwrap()) {
+      self.parse_line(&line);
+    }
+
+    self.active += 1;
+  }
+
+  fn parse_line(&mut self, line: &str) {
+    let trimmed = line.trim();
+
+    if trimmed.is_empty() {
+      self.in_reasons = false;
+      self.requested = None;
+      return;
+    }
+
+    if trimmed == SELECTION_REASONS {
+      self.in_reasons = true;
+      return;
+    }
+
+    if self.in_reasons {
+      if let (Some(reason), Some(insight)) = (trimmed.strip_prefix("- "), self.insights[self.active].last_mut()) {
+        insight.reasons.push(reason.to_string());
+      }
+      return;
+    }
+
+    /* `\--- requester` lines at the top of the tree below a module line */
+    if let Some(requester) = line
+      .strip_prefix("\\--- ")
+      .or_else(|| line.strip_prefix("+--- "))
+    {
+      if let Some((idx, version)) = &self.requested {
+        let requester = requester.trim_end_matches(" (*)");
+        let entry = match version.is_empty() {
+          true => requester.to_string(),
+          false => format!("{} ({})", requester, version),
+        };
+
+        let requested_by = &mut self.insights[self.active][*idx].requested_by;
+        if !requested_by.contains(&entry) {
+          requested_by.push(entry);
+        }
+      }
+      return;
+    }
+
+    if let Some((coordinate, requested, selected, reason)) = parse_module_line(line) {
+      let insights = &mut self.insights[self.active];
+
+      /* the first line of a module is the selected one, the next ones head the trees of its requesters */
+      let idx = match insights
+        .iter()
+        .position(|i| i.coordinate == coordinate)
+      {
+        Some(idx) => idx,
+        None => {
+          insights.push(Insight {
+            coordinate,
+            selected_version: selected,
+            reasons: reason.into_iter().collect(),
+            requested_by: Vec::new(),
+          });
+          insights.len() - 1
+        }
+      };
+      self.requested = Some((idx, requested));
+    }
+  }
+}
+
+/// Splits a module line like `group:artifact:2.15.2 -> 2.15.3 (by constraint)` into the coordinate,
+/// the requested and the selected version, and the reason in parentheses (only in older Gradle versions).
+fn parse_module_line(line: &str) -> Option<(String, String, String, Option<String>)> {
+  if line.starts_with(char::is_whitespace) || line.starts_with('>') || line.starts_with('(') {
+    return None;
+  }
+
+  let (line, reason) = match line.split_once(" (") {
+    Some((module, reason)) => (module, Some(reason.trim_end_matches(')').to_string())),
+    None => (line, None),
+  };
+  let (module, selected) = match line.split_once(ARROW) {
+    Some((module, selected)) => (module, Some(selected.to_string())),
+    None => (line, None),
+  };
+
+  let mut parts = module.splitn(3, ':');
+  let (group, artifact) = (parts.next()?, parts.next()?);
+  if group.is_empty() || artifact.is_empty() || group.contains(' ') {
+    return None;
+  }
+  let requested = parts.next().unwrap_or("").to_string();
+
+  Some((
+    format!("{}:{}", group, artifact),
+    requested.clone(),
+    selected.unwrap_or(requested),
+    reason,
+  ))
+}
+
+/// Checks whether a file is a `dependencyInsight` output rather than a `dependencies` one.
+pub fn is_insight<P>(path: P) -> bool
+where
+  P: AsRef<Path>,
+{
+  let file = match fs::File::open(path) {
+    Ok(f) => f,
+    Err(_) => return false,
+  };
+
+  BufReader::new(file)
+    .lines()
+    .map_while(Result::ok)
+    .filter(|l| !l.trim().is_empty())
+    .take(20)
+    .any(|l| l.contains(INSIGHT_TASK) || l.trim() == SELECTION_REASONS)
+}
+
+/// Compares the selection of each module before and after, keeping the modules whose selection
+/// reasons or requesters changed, sorted by coordinate.
+pub fn diff(before: &[Insight], after: &[Insight]) -> Vec<SelectionChange> {
+  let coordinates: BTreeSet<&String> = before
+    .iter()
+    .chain(after.iter())
+    .map(|i| &i.coordinate)
+    .collect();
+
+  coordinates
+    .into_iter()
+    .filter_map(|coordinate| {
+      let b = before.iter().find(|i| &i.coordinate == coordinate);
+      let a = after.iter().find(|i| &i.coordinate == coordinate);
+
+      let change = SelectionChange {
+        coordinate: coordinate.clone(),
+        version_before: b.map(|i| i.selected_version.clone()),
+        version_after: a.map(|i| i.selected_version.clone()),
+        reasons_before: b.map(|i| i.reasons.clone()).unwrap_or_default(),
+        reasons_after: a.map(|i| i.reasons.clone()).unwrap_or_default(),
+        requested_by_before: b.map(|i| i.requested_by.clone()).unwrap_or_default(),
+        requested_by_after: a.map(|i| i.requested_by.clone()).unwrap_or_default(),
+      };
+
+      match change.reasons_before != change.reasons_after
+        || change.requested_by_before != change.requested_by_after
+        || change.version_before != change.version_after
+      {
+        true => Some(change),
+        false => None,
+      }
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parse(lines: &[&str]) -> Vec<Insight> {
+    let mut parser = InsightParser::new();
+    lines.iter().for_each(|l| parser.parse_line(l));
+    parser.insights[0].clone()
+  }
+
+  #[test]
+  fn it_parses_selection_reasons_and_requesters() {
+    let insights = parse(&[
+      "> Task :dependencyInsight",
+      "com.fasterxml.jackson.core:jackson-databind:2.15.3",
+      "  Variant compile:",
+      "    | Attribute Name    | Provided | Requested    |",
+      "    |-------------------|----------|--------------|",
+      "    | org.gradle.status | release  |              |",
+      "",
+      "   Selection reasons:",
+      "      - By constraint : dependency-management",
+      "      - By conflict resolution : between versions 2.15.3 and 2.15.2",
+      "",
+      "com.fasterxml.jackson.core:jackson-databind:2.15.3",
+      "\\--- com.fasterxml.jackson:jackson-bom:2.15.3",
+      "     \\--- compileClasspath",
+      "",
+      "com.fasterxml.jackson.core:jackson-databind:2.15.2 -> 2.15.3",
+      "\\--- org.springframework.boot:spring-boot-starter-json:3.1.4",
+      "     \\--- compileClasspath",
+      "",
+      "(c) - A dependency constraint, not a dependency.",
+    ]);
+
+    assert_eq!(
+      insights,
+      vec![Insight {
+        coordinate: "com.fasterxml.jackson.core:jackson-databind".to_string(),
+        selected_version: "2.15.3".to_string(),
+        reasons: vec![
+          "By constraint : dependency-management".to_string(),
+          "By conflict resolution : between versions 2.15.3 and 2.15.2".to_string(),
+        ],
+        requested_by: vec![
+          "com.fasterxml.jackson:jackson-bom:2.15.3 (2.15.3)".to_string(),
+          "org.springframework.boot:spring-boot-starter-json:3.1.4 (2.15.2)".to_string(),
+        ],
+      }]
+    );
+  }
+
+  #[test]
+  fn it_reads_the_reason_of_older_outputs_and_diffs_reasons() {
+    let before = parse(&[
+      "org.yaml:snakeyaml:1.33 (conflict resolution)",
+      "   variant \"compile\" [",
+      "   ]",
+      "",
+      "org.yaml:snakeyaml:1.33",
+      "\\--- org.springframework.boot:spring-boot-starter:3.1.3",
+    ]);
+    let after = parse(&[
+      "org.yaml:snakeyaml:2.0 (by constraint)",
+      "",
+      "org.yaml:snakeyaml:1.33 -> 2.0",
+      "\\--- org.springframework.boot:spring-boot-starter:3.1.3",
+    ]);
+
+    assert_eq!(before[0].reasons, vec!["conflict resolution"]);
+
+    let changes = diff(&before, &after);
+
+    assert_eq!(changes.len(), 1);
+    assert_eq!(changes[0].version_before.as_deref(), Some("1.33"));
+    assert_eq!(changes[0].version_after.as_deref(), Some("2.0"));
+    assert_eq!(changes[0].reasons_after, vec!["by constraint"]);
+    assert!(diff(&before, &before).is_empty());
+  }
+}
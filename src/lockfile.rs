@@ -0,0 +1,242 @@
+use rustc_hash::{FxHashMap, FxHashSet};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::Path;
+
+use crate::parser::BUILDSCRIPT_NAMESPACE;
+use crate::types::{Dependency, GradleEntry, TempList, Version, Versions, NOT_APPLICABLE};
+
+/// Comment lines the lockfiles written by Gradle start with.
+const LOCKFILE_HEADER: &str = concat!(
+  "# This is a Gradle generated file for dependency locking.\n",
+  "# Manual edits can break the build and are not advised.\n",
+  "# This file is expected to be part of source control.\n",
+);
+
+/// Reads a `gradle.lockfile` into a dependency map keyed by `group:artifact` like the `DependencyParser` ones. \
+/// Each line locks a module for some configurations, e.g.
+/// `com.google.guava:guava:32.1.2-jre=compileClasspath,runtimeClasspath`. The `empty=` line of the
+/// configurations without dependencies is skipped.
+pub fn from_file<P>(path: P) -> std::io::Result<FxHashMap<String, Dependency>>
+where
+  P: AsRef<Path>,
+{
+  parse(&fs::read_to_string(path)?)
+}
+
+pub fn parse(content: &str) -> std::io::Result<FxHashMap<String, Dependency>> {
+  let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+
+  for (idx, line) in content.lines().enumerate() {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') || line.starts_with("empty=") {
+      continue;
+    }
+
+    let (namespace, name, version, configurations) = match parse_line(line) {
+      Some(parsed) => parsed,
+      None => {
+        return Err(std::io::Error::other(format!(
+          "line {}: expected `group:artifact:version=configurations`, got `{}`",
+          idx + 1,
+          line
+        )))
+      }
+    };
+
+    let dep = dep_map
+      .entry(format!("{}:{}", namespace, name))
+      .or_insert_with(|| Dependency {
+        name: name.to_string(),
+        namespace: namespace.to_string(),
+        gradle_entries: Vec::new(),
+      });
+
+    dep.gradle_entries.extend(
+      configurations
+        .split(',')
+        .filter(|c| !c.is_empty())
+        .map(|c| GradleEntry {
+          gradle_config_name: c.to_string(),
+          versions: Versions {
+            transitive: vec![Version::Transitive(version.to_string())],
+            pinned: Version::NotApplicable,
+          },
+        }),
+    );
+  }
+
+  Ok(dep_map)
+}
+
+/// Writes the resolved versions of the given configurations as a lockfile, sorted like Gradle writes them:
+/// one line per module and version with its configurations, then the configurations without dependencies.
+pub fn write(resolved: &[(String, Vec<TempList>)], configurations: &[String]) -> String {
+  let mut lines: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+
+  for (coordinate, versions) in resolved.iter() {
+    for temp in versions
+      .iter()
+      .filter(|t| t.version != NOT_APPLICABLE && configurations.contains(&t.gradle_config_name))
+    {
+      lines
+        .entry(format!("{}:{}", coordinate, temp.version))
+        .or_default()
+        .insert(&temp.gradle_config_name);
+    }
+  }
+
+  let locked: BTreeSet<&str> = lines.values().flatten().copied().collect();
+  let empty: BTreeSet<&str> = configurations
+    .iter()
+    .map(|c| c.as_str())
+    .filter(|c| !locked.contains(c))
+    .collect();
+
+  let mut out = LOCKFILE_HEADER.to_string();
+
+  for (module, configurations) in lines.iter() {
+    out.push_str(&format!(
+      "{}={}\n",
+      module,
+      configurations
+        .iter()
+        .copied()
+        .collect::<Vec<&str>>()
+        .join(",")
+    ));
+  }
+
+  out.push_str(&format!(
+    "empty={}\n",
+    empty.into_iter().collect::<Vec<&str>>().join(",")
+  ));
+  out
+}
+
+/// Whether Gradle can lock a configuration in the project lockfile: not one it can't resolve (marked `(n)`),
+/// nor one of a subproject or of the buildscript, which have lockfiles of their own.
+pub fn is_lockable(gradle_config_name: &str, unresolvable: &FxHashSet<String>) -> bool {
+  !gradle_config_name.starts_with(':')
+    && !gradle_config_name.starts_with(BUILDSCRIPT_NAMESPACE)
+    && !unresolvable.contains(gradle_config_name)
+}
+
+/// Splits a lockfile line into its group, artifact, version and comma-separated configurations.
+fn parse_line(line: &str) -> Option<(&str, &str, &str, &str)> {
+  let (module, configurations) = line.split_once('=')?;
+  let mut parts = module.splitn(3, ':');
+
+  match (parts.next(), parts.next(), parts.next()) {
+    (Some(group), Some(artifact), Some(version))
+      if !group.is_empty() && !artifact.is_empty() && !version.is_empty() =>
+    {
+      Some((group, artifact, version, configurations))
+    }
+    _ => None,
+  }
+}
+
+/// Checks whether a file is a Gradle lockfile: either it starts with the Gradle header,
+/// or its first lines all are lockfile lines.
+pub fn is_lockfile<P>(path: P) -> bool
+where
+  P: AsRef<Path>,
+{
+  let content = match fs::read_to_string(path) {
+    Ok(c) => c,
+    Err(_) => return false,
+  };
+
+  let mut lines = content
+    .lines()
+    .map(|l| l.trim())
+    .filter(|l| !l.is_empty())
+    .peekable();
+
+  if lines.peek().copied() == LOCKFILE_HEADER.lines().next() {
+    return true;
+  }
+
+  let mut lines = lines
+    .filter(|l| !l.starts_with('#'))
+    .take(10)
+    .peekable();
+
+  lines.peek().is_some() && lines.all(|l| l.starts_with("empty=") || parse_line(l).is_some())
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::temp;
+
+  #[test]
+  fn it_parses_a_lockfile_into_a_dependency_map() {
+    let dep_map = parse(
+      "# This is a Gradle generated file for dependency locking.\n\
+       # Manual edits can break the build and are not advised.\n\
+       # This file is expected to be part of source control.\n\
+       com.google.guava:guava:32.1.2-jre=compileClasspath,runtimeClasspath\n\
+       org.yaml:snakeyaml:2.0=runtimeClasspath\n\
+       empty=annotationProcessor\n",
+    )
+    .unwrap();
+
+    assert_eq!(dep_map.len(), 2);
+
+    let guava = &dep_map["com.google.guava:guava"];
+    assert_eq!(guava.namespace, "com.google.guava");
+    assert_eq!(guava.gradle_entries.len(), 2);
+    assert_eq!(guava.gradle_entries[1].gradle_config_name, "runtimeClasspath");
+    assert_eq!(guava.gradle_entries[1].versions.transitive[0].to_string(), "32.1.2-jre");
+    assert!(!guava.gradle_entries[1].versions.pinned.is_applicable());
+  }
+
+  #[test]
+  fn it_writes_a_lockfile_sorted_like_gradle() {
+    let resolved = vec![
+      (
+        "org.yaml:snakeyaml".to_string(),
+        vec![temp("runtimeClasspath", "2.0"), temp("compileClasspath", "2.0")],
+      ),
+      (
+        "com.google.guava:guava".to_string(),
+        vec![
+          temp("testRuntimeClasspath", "32.1.2-jre"),
+          temp("compileClasspath", "31.1-jre"),
+          temp("implementation", "31.1-jre"),
+        ],
+      ),
+    ];
+    let configurations: Vec<String> = [
+      "annotationProcessor",
+      "compileClasspath",
+      "runtimeClasspath",
+      "testRuntimeClasspath",
+    ]
+    .iter()
+    .map(|c| c.to_string())
+    .collect();
+
+    let lockfile = write(&resolved, &configurations);
+
+    assert_eq!(
+      lockfile.lines().skip(3).collect::<Vec<&str>>(),
+      vec![
+        "com.google.guava:guava:31.1-jre=compileClasspath",
+        "com.google.guava:guava:32.1.2-jre=testRuntimeClasspath",
+        "org.yaml:snakeyaml:2.0=compileClasspath,runtimeClasspath",
+        "empty=annotationProcessor",
+      ]
+    );
+    assert_eq!(parse(&lockfile).unwrap().len(), 2);
+  }
+
+  #[test]
+  fn it_rejects_malformed_lines() {
+    let err = parse("com.google.guava:guava=compileClasspath\n").unwrap_err();
+
+    assert!(err.to_string().starts_with("line 1:"));
+  }
+}
@@ -1,91 +1,716 @@
+mod acknowledge;
+mod alignment;
+mod bazel;
+mod catalog;
+mod config_file;
+mod configurations;
+mod consistency;
+mod document;
+mod filter;
+mod gate;
+mod glob;
+mod helpers;
+mod insight;
+mod lockfile;
+mod maven;
+mod ordering;
 mod parser;
+mod plugins;
+mod policy;
+mod projects;
+mod report;
+mod snapshot;
+mod summary;
+mod terminal;
+mod tree_diff;
 mod types;
-use clap::Parser;
+use clap::{Parser, ValueEnum};
 use handlebars::Handlebars;
 use std::fs;
-use std::io::ErrorKind;
 use std::io::{BufRead, BufReader, Write};
 use std::path::{Path, PathBuf};
-use types::ProcessedDependencyObject;
+use types::{ChangeKind, ProcessedDependencyObject, TempList};
 
 use crate::types::Version;
+use acknowledge::Acknowledgements;
+use alignment::{Family, Misalignment};
+use catalog::{Catalog, CatalogReport};
+use configurations::{ConfigurationEvent, DEFAULT_RENAME_SIMILARITY};
+use document::{DiffDocument, Side};
+use filter::Filter;
+use gate::FailOn;
+use insight::{InsightParser, SelectionChange};
+use ordering::{GroupBy, SortBy};
 use parser::DependencyParser;
+use policy::Policy;
+use report::{HtmlReport, ReportInputs};
+use snapshot::Snapshot;
+use terminal::ColorChoice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum OutputFormat {
+  /// Coloured diff grouped by configuration, printed to stdout
+  Terminal,
+  /// HTML report (or the output of `--template`) written to the `--output` file
+  Html,
+  /// JSON printed to stdout
+  Json,
+}
 
 #[derive(Parser)]
 #[command(author, version, about = "Gradle Deps Differ - Diffs two Gradle dependencies files", long_about = None)]
 struct Cli {
-  /// Path to file listing Gradle dependencies before
+  /// Path to file listing Gradle dependencies before (or to a `gradle.lockfile`, a Maven `dependency:tree`
+  /// output, a Bazel `maven_install.json`, a previous JSON output, or a `dependencyInsight` output to diff the
+  /// selection reasons of)
   #[arg(short = 'b', long, value_name = "path-to-deps-before-file")]
   file_before: Option<PathBuf>,
 
-  /// Path to file listing Gradle dependencies after
+  /// Path to file listing Gradle dependencies after (or to a `gradle.lockfile`, a Maven `dependency:tree`
+  /// output, a Bazel `maven_install.json`, a previous JSON output, or a `dependencyInsight` output)
   #[arg(short = 'a', long, value_name = "path-to-deps-after-file")]
   file_after: Option<PathBuf>,
 
-  /// Output JSON
+  /// Output format
+  #[arg(short, long, value_enum, default_value_t = OutputFormat::Terminal)]
+  format: OutputFormat,
+
+  /// Output JSON (shorthand for `--format json`)
   #[arg(short, long, action)]
   json: bool,
+
+  /// Only include configurations matching this glob (can be repeated)
+  #[arg(long, value_name = "configuration-glob")]
+  include_configuration: Vec<String>,
+
+  /// Exclude configurations matching this glob (can be repeated)
+  #[arg(long, value_name = "configuration-glob")]
+  exclude_configuration: Vec<String>,
+
+  /// Only include dependencies whose `group:artifact` matches this glob (can be repeated)
+  #[arg(long, value_name = "dependency-glob")]
+  include_dependency: Vec<String>,
+
+  /// Exclude dependencies whose `group:artifact` matches this glob (can be repeated)
+  #[arg(long, value_name = "dependency-glob")]
+  exclude_dependency: Vec<String>,
+
+  /// Leave out unchanged dependencies
+  #[arg(long, action)]
+  only_changed: bool,
+
+  /// Only include changes of this kind (can be repeated)
+  #[arg(long, value_enum, value_name = "kind")]
+  change_kind: Vec<ChangeKind>,
+
+  /// Compare configuration `from` as configuration `to`, e.g. `releaseRuntimeClasspath=runtimeClasspath`
+  /// (can be repeated)
+  #[arg(long, value_name = "from=to", value_parser = parse_pair)]
+  alias_configuration: Vec<(String, String)>,
+
+  /// Minimum similarity (from 0 to 1) of the dependencies of a removed and an added configuration for them to be
+  /// reported as a rename
+  #[arg(long, value_name = "similarity", default_value_t = DEFAULT_RENAME_SIMILARITY, value_parser = parse_similarity)]
+  rename_similarity: f64,
+
+  /// Instead of diffing, check the `--file-after` output (or the only one given) for dependencies resolving to
+  /// different versions in related configurations
+  #[arg(long, action)]
+  consistency: bool,
+
+  /// Configurations to compare with `--consistency`, where `*` matches any part of a configuration name, e.g.
+  /// `*CompileClasspath=*RuntimeClasspath` (can be repeated; compile vs runtime and main vs test when omitted)
+  #[arg(long, value_name = "first=second", value_parser = parse_pair)]
+  consistency_pair: Vec<(String, String)>,
+
+  /// Instead of diffing, check the `--file-after` output (or the only one given) for artifact families whose members
+  /// resolve to mixed versions in a configuration
+  #[arg(long, action, conflicts_with = "consistency")]
+  alignment: bool,
+
+  /// Artifacts normally sharing a version, as `name=glob[,glob...]` on `group:artifact` (`!` excludes), checked with
+  /// the built-in families by `--alignment` and for newly misaligned families in diffs (can be repeated)
+  #[arg(long, value_name = "name=globs")]
+  family: Vec<Family>,
+
+  /// Also treat the artifacts of each group as a family
+  #[arg(long, action)]
+  family_by_group: bool,
+
+  /// Instead of diffing, show the version of each dependency in each project of the `--file-after` output (or the only
+  /// one given), highlighting the dependencies resolving to different versions across projects
+  #[arg(long, action, conflicts_with_all = ["consistency", "alignment"])]
+  project_alignment: bool,
+
+  /// Configuration compared across projects by `--project-alignment`
+  #[arg(long, value_name = "configuration", default_value = "runtimeClasspath")]
+  project_configuration: String,
+
+  /// With `--project-alignment`, exit with a non-zero code when a dependency resolves to different versions
+  /// across projects
+  #[arg(long, action, requires = "project_alignment")]
+  fail_on_divergence: bool,
+
+  /// Order of dependencies and of their configurations in every output
+  #[arg(long, value_enum, value_name = "order", default_value_t = SortBy::Name)]
+  sort_by: SortBy,
+
+  /// Group changes by this key in every output (terminal output is grouped by configuration otherwise)
+  #[arg(long, value_enum, value_name = "key")]
+  group_by: Option<GroupBy>,
+
+  /// When to colourise terminal output
+  #[arg(long, value_enum, value_name = "when", default_value_t = ColorChoice::Auto)]
+  color: ColorChoice,
+
+  /// Handlebars template to render the report with instead of the built-in one (implies `--format html`)
+  #[arg(long, value_name = "path-to-template")]
+  template: Option<PathBuf>,
+
+  /// Directory of `*.hbs` partials to register for the report template, named after their file stem
+  #[arg(long, value_name = "path-to-partials-dir")]
+  partials: Option<PathBuf>,
+
+  /// Exit with a non-zero code when the condition is met (can be repeated)
+  #[arg(long, value_enum, value_name = "condition")]
+  fail_on: Vec<FailOn>,
+
+  /// Exit with a non-zero code when anything changed in a configuration matching this glob (can be repeated)
+  #[arg(long, value_name = "configuration-glob")]
+  fail_on_configuration: Vec<String>,
+
+  /// Check the diff against the rules of a TOML or YAML policy file, reporting violations instead of the diff
+  #[arg(long, value_name = "path-to-policy-file")]
+  check: Option<PathBuf>,
+
+  /// TOML or YAML file of reviewed changes to mark as acknowledged and exclude from `--fail-on` and `--check`
+  #[arg(long, value_name = "path-to-acknowledgements-file")]
+  acknowledgements: Option<PathBuf>,
+
+  /// Dependency snapshot to diff the `--file-after` output against, instead of a `--file-before` output
+  #[arg(long, value_name = "path-to-snapshot")]
+  baseline: Option<PathBuf>,
+
+  /// Rewrite the `--baseline` snapshot from the `--file-after` output (creating it if missing)
+  #[arg(long, action, requires = "baseline")]
+  update_baseline: bool,
+
+  /// Instead of diffing, write the resolved versions of the `--file-after` output (or the only one given) as a
+  /// `gradle.lockfile` to this path
+  #[arg(long, value_name = "path-to-lockfile")]
+  write_lockfile: Option<PathBuf>,
+
+  /// `libs.versions.toml` of the after revision, to annotate the dependencies after with their catalog alias and
+  /// declared version and flag the ones resolved to another version
+  #[arg(long, value_name = "path-to-catalog")]
+  catalog: Option<PathBuf>,
+
+  /// `libs.versions.toml` of the before revision, to report the catalog changes (with `--catalog`)
+  #[arg(long, value_name = "path-to-catalog", requires = "catalog")]
+  catalog_before: Option<PathBuf>,
+
+  /// Print the JSON Schema of the JSON output and exit
+  #[arg(long, action)]
+  print_schema: bool,
+
+  /// Path of the report file written by `--format html`
+  #[arg(
+    short,
+    long,
+    value_name = "path-to-report",
+    default_value = "gradle-dependencies-diff-report.html"
+  )]
+  output: PathBuf,
 }
 
 fn main() -> std::io::Result<()> {
   let template = include_bytes!("../report/report_template.hbs");
   let cli = Cli::parse();
 
-  let file_before = match cli.file_before {
-    Some(f) => f,
-    None => panic!("File 1 not provided"),
+  if cli.print_schema {
+    print!("{}", document::schema());
+    return Ok(());
+  }
+
+  let mut parser = Box::new(DependencyParser::new());
+  parser.configuration_aliases = cli.alias_configuration.iter().cloned().collect();
+
+  let format = match (cli.json, &cli.template) {
+    (true, _) => OutputFormat::Json,
+    (false, Some(_)) => OutputFormat::Html,
+    (false, None) => cli.format,
   };
 
-  let file_after = match cli.file_after {
-    Some(f) => f,
-    None => panic!("File 2 not provided"),
+  let filter = Filter {
+    include_configurations: cli.include_configuration.clone(),
+    exclude_configurations: cli.exclude_configuration.clone(),
+    include_dependencies: cli.include_dependency.clone(),
+    exclude_dependencies: cli.exclude_dependency.clone(),
+    only_changed: cli.only_changed,
+    change_kinds: cli.change_kind.clone(),
   };
 
-  let mut parser = Box::new(DependencyParser::new());
+  if let (Some(before), Some(after)) = (&cli.file_before, &cli.file_after) {
+    if insight::is_insight(before) || insight::is_insight(after) {
+      /* selection changes have no versions per configuration to gate on or to render in the HTML report */
+      let unsupported: Vec<&str> = [
+        ("--fail-on", !cli.fail_on.is_empty()),
+        ("--fail-on-configuration", !cli.fail_on_configuration.is_empty()),
+        ("--check", cli.check.is_some()),
+        ("--acknowledgements", cli.acknowledgements.is_some()),
+        ("--format html", matches!(format, OutputFormat::Html)),
+      ]
+      .into_iter()
+      .filter(|(_, used)| *used)
+      .map(|(flag, _)| flag)
+      .collect();
+      if !unsupported.is_empty() {
+        panic!(
+          "{} can't be used with dependencyInsight outputs",
+          unsupported.join(", ")
+        );
+      }
+
+      let changes = diff_insights(before, after, &filter);
+
+      match format {
+        OutputFormat::Json => {
+          let res = serde_json::json!({ "selection_changes": changes });
+          println!("{}", serde_json::to_string_pretty(&res).unwrap());
+        }
+        _ => print!("{}", terminal::render_selection_changes(&changes, cli.color.enabled())),
+      }
 
-  if validate_input_file(&file_before).is_err() {
-    panic!("Provided gradle dependencies before file is invalid");
+      return Ok(());
+    }
   }
 
-  if validate_input_file(&file_after).is_err() {
-    panic!("Provided gradle dependencies after file is invalid");
+  if let Some(lockfile_path) = &cli.write_lockfile {
+    let file = match cli.file_after.as_ref().or(cli.file_before.as_ref()) {
+      Some(f) => f,
+      None => panic!("File not provided"),
+    };
+    parse_input_file(&mut parser, file, Side::After);
+
+    let mut configurations: Vec<String> = parser.trees[0]
+      .iter()
+      .map(|t| t.gradle_config_name.clone())
+      .chain(
+        parser
+          .resolved_versions(0)
+          .into_iter()
+          .flat_map(|(_, versions)| versions.into_iter().map(|t| t.gradle_config_name)),
+      )
+      .filter(|c| lockfile::is_lockable(c, &parser.unresolvable_configurations[0]) && filter.matches_configuration(c))
+      .collect();
+    configurations.sort();
+    configurations.dedup();
+
+    fs::write(
+      lockfile_path,
+      lockfile::write(&resolved_versions(&parser, &filter, 0), &configurations),
+    )?;
+    eprintln!("Wrote lockfile {}", lockfile_path.display());
+
+    return Ok(());
   }
 
-  match read_file(&file_before) {
-    Ok(file) => {
-      parser.parse_file(file);
+  if cli.consistency || cli.alignment || cli.project_alignment {
+    let file = match cli.file_after.as_ref().or(cli.file_before.as_ref()) {
+      Some(f) => f,
+      None => panic!("File not provided"),
+    };
+    parse_input_file(&mut parser, file, Side::After);
+
+    let resolved = resolved_versions(&parser, &filter, 0);
+
+    let (res, found, exit_code) = if cli.consistency {
+      let mut configurations: Vec<String> = resolved
+        .iter()
+        .flat_map(|(_, versions)| versions.iter().map(|t| t.gradle_config_name.clone()))
+        .collect();
+      configurations.sort();
+      configurations.dedup();
+
+      let pairs: Vec<(String, String)> = match cli.consistency_pair.is_empty() {
+        true => consistency::DEFAULT_PAIRS
+          .iter()
+          .map(|(a, b)| (a.to_string(), b.to_string()))
+          .collect(),
+        false => cli.consistency_pair.clone(),
+      };
+      let inconsistencies = consistency::check(&resolved, &consistency::expand_pairs(&pairs, &configurations));
+
+      let res = match format {
+        OutputFormat::Json => {
+          serde_json::to_string_pretty(&serde_json::json!({ "inconsistencies": inconsistencies })).unwrap() + "\n"
+        }
+        _ => terminal::render_inconsistencies(&inconsistencies, cli.color.enabled()),
+      };
+      (res, !inconsistencies.is_empty(), consistency::INCONSISTENCY_EXIT_CODE)
+    } else if cli.alignment {
+      let misalignments = alignment::detect(&resolved, &alignment::families(&cli.family), cli.family_by_group);
+
+      let res = match format {
+        OutputFormat::Json => {
+          serde_json::to_string_pretty(&serde_json::json!({ "misalignments": misalignments })).unwrap() + "\n"
+        }
+        _ => terminal::render_misalignments(&misalignments, cli.color.enabled()),
+      };
+      (res, !misalignments.is_empty(), alignment::MISALIGNMENT_EXIT_CODE)
+    } else {
+      let matrix = projects::matrix(&resolved, &cli.project_configuration);
+
+      let res = match format {
+        OutputFormat::Json => serde_json::to_string_pretty(&matrix).unwrap() + "\n",
+        _ => terminal::render_project_matrix(&matrix, cli.color.enabled()),
+      };
+      (
+        res,
+        cli.fail_on_divergence && matrix.divergent_rows().next().is_some(),
+        projects::DIVERGENCE_EXIT_CODE,
+      )
+    };
+
+    print!("{}", res);
+
+    if found {
+      std::io::stdout().flush()?;
+      std::process::exit(exit_code);
     }
-    Err(e) => panic!("Error encountered while trying to open file: {}", e),
+
+    return Ok(());
+  }
+
+  match &cli.baseline {
+    Some(baseline) => {
+      let file_after = match &cli.file_after {
+        Some(f) => f,
+        None => panic!("File 2 not provided"),
+      };
+
+      let snapshot = match Snapshot::from_file(baseline) {
+        Ok(s) => s,
+        Err(_) if cli.update_baseline && !baseline.exists() => Snapshot::default(),
+        Err(e) => panic!("Error encountered while trying to read baseline snapshot: {}", e),
+      };
+
+      parser.load_dep_map(snapshot.to_dep_map());
+      parse_input_file(&mut parser, file_after, Side::After);
+    }
+    None => {
+      let file_before = match &cli.file_before {
+        Some(f) => f,
+        None => panic!("File 1 not provided"),
+      };
+
+      let file_after = match &cli.file_after {
+        Some(f) => f,
+        None => panic!("File 2 not provided"),
+      };
+
+      parse_input_file(&mut parser, file_before, Side::Before);
+      parse_input_file(&mut parser, file_after, Side::After);
+
+      /* a lockfile only has the configurations Gradle locks, so drop the other ones from the other side */
+      if lockfile::is_lockfile(file_before) {
+        parser.retain_lockable_configurations(1);
+      }
+      if lockfile::is_lockfile(file_after) {
+        parser.retain_lockable_configurations(0);
+      }
+    }
+  }
+
+  /* filter before anything else, so that summaries, gating and policy checks only see what is reported */
+  let mut values: Vec<ProcessedDependencyObject> =
+    filter.apply(parser.compare_versions(), &parser.configuration_aliases);
+  parser
+    .trees
+    .iter_mut()
+    .for_each(|t| filter.apply_to_trees(t));
+
+  let configuration_sets = [0, 1].map(|side| {
+    let mut sets = configurations::configuration_sets(
+      &parser.dep_maps[side],
+      &parser.trees[side],
+      &parser.configuration_aliases,
+    );
+    sets.retain(|name, _| filter.matches_configuration(name));
+    sets
+  });
+  let configuration_events =
+    configurations::detect(&configuration_sets[0], &configuration_sets[1], cli.rename_similarity);
+  /* the changes of whole configurations are reported once, as their events */
+  configurations::fold(&mut values, &configuration_events);
+  ordering::sort(&mut values, cli.sort_by);
+
+  let families = alignment::families(&cli.family);
+  let [misaligned_before, misaligned_after] = [0, 1].map(|side| {
+    alignment::detect(
+      &resolved_versions(&parser, &filter, side),
+      &families,
+      cli.family_by_group,
+    )
+  });
+  let newly_misaligned = alignment::newly_misaligned(&misaligned_before, misaligned_after);
+
+  let catalog = match &cli.catalog {
+    Some(path) => {
+      let after = read_catalog(path);
+      CatalogReport {
+        changes: match &cli.catalog_before {
+          Some(before) => catalog::diff(&read_catalog(before), &after),
+          None => Vec::new(),
+        },
+        annotations: catalog::annotate(&resolved_versions(&parser, &filter, 1), &after),
+      }
+    }
+    None => CatalogReport::default(),
   };
 
-  match read_file(file_after) {
+  if let Some(path) = &cli.acknowledgements {
+    let acknowledgements = match Acknowledgements::from_file(path) {
+      Ok(a) => a,
+      Err(e) => panic!("Error encountered while trying to read acknowledgements file: {}", e),
+    };
+
+    for warning in acknowledgements.apply(&mut values, &acknowledge::today()) {
+      eprintln!("WARNING: {}", warning);
+    }
+  }
+
+  if let Some(path) = &cli.check {
+    let policy = match Policy::from_file(path) {
+      Ok(p) => p,
+      Err(e) => panic!("Error encountered while trying to read policy file: {}", e),
+    };
+
+    let violations = policy.check(&values);
+
+    match format {
+      OutputFormat::Json => {
+        let res = serde_json::json!({ "violations": violations });
+        println!("{}", serde_json::to_string_pretty(&res).unwrap());
+      }
+      _ => print!("{}", terminal::render_violations(&violations, cli.color.enabled())),
+    }
+
+    if !violations.is_empty() {
+      std::io::stdout().flush()?;
+      std::process::exit(policy::VIOLATION_EXIT_CODE);
+    }
+
+    return Ok(());
+  }
+
+  match format {
+    OutputFormat::Terminal => {
+      let group_by = cli.group_by.unwrap_or(GroupBy::Configuration);
+      let groups = ordering::group(&values, group_by, &parser.trees);
+      print!(
+        "{}",
+        terminal::render_summary(&summary::summarize(&values), cli.color.enabled())
+      );
+      print!(
+        "{}",
+        terminal::render_configuration_events(&configuration_events, cli.color.enabled())
+      );
+      print!(
+        "{}",
+        terminal::render_plugin_changes(&plugins::changes(&values), cli.color.enabled())
+      );
+      print!(
+        "{}",
+        terminal::render_newly_misaligned(&newly_misaligned, cli.color.enabled())
+      );
+      print!("{}", terminal::render_catalog(&catalog, cli.color.enabled()));
+      print!("{}", terminal::render(&groups, group_by, cli.color.enabled()));
+    }
+    OutputFormat::Json => {
+      let document = DiffDocument {
+        group_by: cli.group_by,
+        groups: match cli.group_by {
+          Some(group_by) => ordering::group(&values, group_by, &parser.trees),
+          None => Vec::new(),
+        },
+        configuration_events: configuration_events.clone(),
+        newly_misaligned: newly_misaligned.clone(),
+        catalog: catalog.clone(),
+        ..DiffDocument::new(values.clone())
+      };
+      println!("{}", serde_json::to_string_pretty(&document).unwrap());
+    }
+    OutputFormat::Html => {
+      let mut handlebars = Handlebars::new();
+      helpers::register(&mut handlebars);
+
+      if let Some(dir) = &cli.partials {
+        if let Err(e) = register_partials(&mut handlebars, dir) {
+          panic!("Error encountered while trying to register partials: {}", e);
+        }
+      }
+
+      let template = match &cli.template {
+        Some(path) => match fs::read_to_string(path) {
+          Ok(t) => t,
+          Err(e) => panic!("Error encountered while trying to open template: {}", e),
+        },
+        None => std::str::from_utf8(template).unwrap().to_string(),
+      };
+
+      let report = build_report(
+        &parser,
+        &values,
+        configuration_events,
+        newly_misaligned,
+        catalog,
+        cli.group_by,
+      );
+      generate_report(&mut handlebars, &template, &cli.output, &report);
+    }
+  }
+
+  if cli.update_baseline {
+    let baseline = cli.baseline.as_ref().unwrap();
+    Snapshot::from_dep_map(&parser.dep_maps[1]).write_to_file(baseline)?;
+    eprintln!("Updated baseline snapshot {}", baseline.display());
+
+    /* the changes are accepted by updating the baseline, so they don't fail the build */
+    return Ok(());
+  }
+
+  let mut conditions = cli.fail_on.clone();
+  if !cli.fail_on_configuration.is_empty() {
+    conditions.push(FailOn::Configuration);
+  }
+
+  if !conditions.is_empty() {
+    let result = gate::evaluate(&values, &conditions, &cli.fail_on_configuration);
+
+    /* machine-readable summary on stderr, so it doesn't mix with the report on stdout */
+    eprintln!("{}", serde_json::to_string(&result).unwrap());
+
+    if !result.passed {
+      std::io::stdout().flush()?;
+      std::process::exit(result.exit_code);
+    }
+  }
+
+  Ok(())
+}
+
+/// Diffs the selection reasons of two `dependencyInsight` outputs.
+fn diff_insights(before: &Path, after: &Path, filter: &Filter) -> Vec<SelectionChange> {
+  let mut parser = InsightParser::new();
+
+  for (path, description) in [(before, "before"), (after, "after")] {
+    if !insight::is_insight(path) {
+      panic!(
+        "Provided gradle dependencies {} file is not a dependencyInsight output like the other one",
+        description
+      );
+    }
+
+    match read_file(path) {
+      Ok(file) => parser.parse_file(file),
+      Err(e) => panic!("Error encountered while trying to open file: {}", e),
+    };
+  }
+
+  let mut changes = insight::diff(&parser.insights[0], &parser.insights[1]);
+  changes.retain(|c| filter.matches_dependency(&c.coordinate));
+  changes
+}
+
+/// Resolved versions of one input, without the dependencies and configurations left out by the filter.
+fn resolved_versions(parser: &DependencyParser, filter: &Filter, side: usize) -> Vec<(String, Vec<TempList>)> {
+  let mut resolved = parser.resolved_versions(side);
+
+  resolved.retain_mut(|(coordinate, versions)| {
+    versions.retain(|t| filter.matches_configuration(&t.gradle_config_name));
+    filter.matches_dependency(coordinate) && !versions.is_empty()
+  });
+
+  resolved
+}
+
+/// Parses a Gradle output or lockfile, a Maven tree or a Bazel lock, or reads the given side of a diff document
+/// (a previous JSON output).
+fn parse_input_file(parser: &mut DependencyParser, path: &Path, side: Side) {
+  if bazel::is_maven_install(path) {
+    if let Err(e) = bazel::parse_file(parser, path) {
+      panic!("Error encountered while trying to read maven_install.json: {}", e);
+    }
+    return;
+  }
+
+  if document::is_document(path) {
+    match DiffDocument::from_file(path) {
+      Ok(d) => parser.load_dep_map(d.dep_map(side)),
+      Err(e) => panic!("Error encountered while trying to read JSON input: {}", e),
+    };
+    return;
+  }
+
+  if lockfile::is_lockfile(path) {
+    match lockfile::from_file(path) {
+      Ok(dep_map) => parser.load_dep_map(dep_map),
+      Err(e) => panic!("Error encountered while trying to read lockfile: {}", e),
+    };
+    return;
+  }
+
+  if maven::is_maven_tree(path) {
+    match read_file(path) {
+      Ok(file) => maven::parse_file(parser, file),
+      Err(e) => panic!("Error encountered while trying to open file: {}", e),
+    };
+    return;
+  }
+
+  if validate_input_file(path).is_err() {
+    let description = match side {
+      Side::Before => "before",
+      Side::After => "after",
+    };
+    panic!("Provided gradle dependencies {} file is invalid", description);
+  }
+
+  match read_file(path) {
     Ok(file) => {
       parser.parse_file(file);
     }
     Err(e) => panic!("Error encountered while trying to open file: {}", e),
   };
+}
 
-  if cli.json {
-    println!("{}", serde_json::to_string_pretty(&parser.compare_versions()).unwrap());
-  } else {
-    let mut handlebars = Handlebars::new();
-    generate_report(&parser, &mut handlebars, &std::str::from_utf8(template).unwrap());
+fn read_catalog(path: &Path) -> Catalog {
+  match Catalog::from_file(path) {
+    Ok(c) => c,
+    Err(e) => panic!("Error encountered while trying to read version catalog: {}", e),
   }
+}
 
-  Ok(())
+fn parse_pair(pair: &str) -> Result<(String, String), String> {
+  match pair.split_once('=') {
+    Some((first, second)) if !first.is_empty() && !second.is_empty() => Ok((first.to_string(), second.to_string())),
+    _ => Err(format!("expected two names separated by `=`, got `{}`", pair)),
+  }
+}
+
+fn parse_similarity(similarity: &str) -> Result<f64, String> {
+  match similarity.parse::<f64>() {
+    Ok(s) if (0.0..=1.0).contains(&s) => Ok(s),
+    _ => Err(format!("expected a number from 0 to 1, got `{}`", similarity)),
+  }
 }
 
 fn validate_input_file<P>(filepath: P) -> std::io::Result<()>
 where
   P: AsRef<Path>,
 {
-  let reader_res = read_file(filepath);
-  let reader: BufReader<fs::File>;
-  if reader_res.is_err() {
-    return Err(reader_res.err().unwrap());
-  } else {
-    reader = reader_res.unwrap();
-  }
+  let reader = read_file(filepath)?;
 
   for line in reader
     .lines()
@@ -93,40 +718,92 @@ where
     .take(10)
     .map(|l| l.unwrap())
   {
-    if line.contains("> Task :dependencies") || line.contains("Root project") {
+    if line.contains("> Task :") && line.ends_with(":dependencies")
+      || line.contains("Root project")
+      || line.starts_with("Project ")
+    {
       return Ok(());
     }
   }
 
-  Err(std::io::Error::new(ErrorKind::Other, "File validation failed"))
+  Err(std::io::Error::other("File validation failed"))
 }
 
 fn read_file<P>(filename: P) -> std::io::Result<BufReader<fs::File>>
 where
   P: AsRef<Path>,
 {
-  return match fs::File::open(filename) {
+  match fs::File::open(filename) {
     Ok(f) => Ok(BufReader::new(f)),
     Err(e) => {
       println!("ERROR: {}", e);
       Err(e)
     }
+  }
+}
+
+/// Registers every `*.hbs` file of `dir` as a partial named after its file stem,
+/// e.g. `header.hbs` can be used as `{{> header}}`.
+fn register_partials<P>(handlebars: &mut Handlebars, dir: P) -> std::io::Result<()>
+where
+  P: AsRef<Path>,
+{
+  for entry in fs::read_dir(dir)? {
+    let path = entry?.path();
+
+    if path.extension().is_some_and(|ext| ext == "hbs") {
+      let name = path.file_stem().unwrap().to_string_lossy().to_string();
+
+      if let Err(e) = handlebars.register_partial(&name, fs::read_to_string(&path)?) {
+        return Err(std::io::Error::other(format!("{}: {}", path.display(), e)));
+      }
+    }
+  }
+
+  Ok(())
+}
+
+fn build_report(
+  parser: &DependencyParser,
+  values: &[ProcessedDependencyObject],
+  configuration_events: Vec<ConfigurationEvent>,
+  newly_misaligned: Vec<Misalignment>,
+  catalog: CatalogReport,
+  group_by: Option<GroupBy>,
+) -> HtmlReport {
+  /* trees are only printed in Gradle outputs, so they can't be diffed against a snapshot or JSON input */
+  let trees = match parser.trees.iter().all(|t| !t.is_empty()) {
+    true => tree_diff::diff(&parser.trees[0], &parser.trees[1]),
+    false => Vec::new(),
   };
+  let groups = match group_by {
+    Some(group_by) => ordering::group(values, group_by, &parser.trees),
+    None => Vec::new(),
+  };
+  report::build(
+    values,
+    ReportInputs {
+      trees,
+      configuration_events,
+      newly_misaligned,
+      catalog,
+      group_by,
+      groups,
+    },
+  )
 }
 
-fn generate_report(parser: &DependencyParser, handlebars: &mut Handlebars, template: &str) {
-  let _ = match handlebars.register_template_string("report_template", template) {
+fn generate_report(handlebars: &mut Handlebars, template: &str, output: &Path, report: &HtmlReport) {
+  match handlebars.register_template_string("report_template", template) {
     Ok(_) => (),
     Err(e) => panic!("{}", e),
   };
 
-  let values: Vec<ProcessedDependencyObject> = parser.compare_versions();
-
-  let mut file = fs::File::create("gradle-dependencies-diff-report.html").unwrap();
+  let mut file = fs::File::create(output).unwrap();
 
   let _ = file.write_all(
     handlebars
-      .render("report_template", &values)
+      .render("report_template", report)
       .unwrap()
       .as_bytes(),
   );
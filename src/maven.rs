@@ -0,0 +1,327 @@
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+use crate::parser::DependencyParser;
+use crate::types::{ParsedDependency, Version};
+
+const LOG_PREFIX: &str = "[INFO]";
+const PLUGIN_IDENTIFIER: &str = "maven-dependency-plugin";
+const TREE_MARKERS: [&str; 2] = ["+- ", "\\- "];
+/// Width of each level of nesting (`|  `).
+const INDENT: usize = 3;
+
+/// Configurations a dependency of each Maven scope is on, like the Java plugin of Gradle would put it.
+const SCOPE_CONFIGURATIONS: [(&str, &[&str]); 5] = [
+  (
+    "compile",
+    &[
+      "compileClasspath",
+      "runtimeClasspath",
+      "testCompileClasspath",
+      "testRuntimeClasspath",
+    ],
+  ),
+  (
+    "provided",
+    &["compileClasspath", "testCompileClasspath", "testRuntimeClasspath"],
+  ),
+  ("runtime", &["runtimeClasspath", "testRuntimeClasspath"]),
+  ("test", &["testCompileClasspath", "testRuntimeClasspath"]),
+  (
+    "system",
+    &["compileClasspath", "testCompileClasspath", "testRuntimeClasspath"],
+  ),
+];
+const CONFIGURATIONS: [&str; 4] = [
+  "compileClasspath",
+  "runtimeClasspath",
+  "testCompileClasspath",
+  "testRuntimeClasspath",
+];
+
+/// A module of the tree of one Maven project.
+#[derive(Debug, Clone, PartialEq)]
+struct MavenNode {
+  depth: usize,
+  namespace: String,
+  name: String,
+  /// Version requested, when the resolved one differs (managed or omitted for conflict).
+  requested: Option<String>,
+  version: String,
+  scope: String,
+  /// Omitted by Maven because it is resolved elsewhere in the tree.
+  repeated: bool,
+}
+
+/// Tree of a Maven project, named after its artifact.
+#[derive(Debug, Default)]
+struct MavenProject {
+  artifact: String,
+  nodes: Vec<MavenNode>,
+}
+
+/// Parses a `mvn dependency:tree` output into the next input of `parser`. \
+/// Each dependency is added to the configurations of its scope, e.g. `runtime` ones to `runtimeClasspath`
+/// and `testRuntimeClasspath`. The configurations of each module of a multi-module output are qualified
+/// by the artifact of the module, like subprojects: `:service-a:runtimeClasspath`.
+pub fn parse_file(parser: &mut DependencyParser, file: BufReader<fs::File>) {
+  let lines: Vec<String> = file.lines().map(|l| l.unwrap()).collect();
+  parse(parser, lines.iter().map(|l| l.as_str()));
+}
+
+fn parse<'a>(parser: &mut DependencyParser, lines: impl Iterator<Item = &'a str>) {
+  let projects = parse_projects(lines);
+  let qualified = projects.len() > 1;
+
+  for project in projects.iter() {
+    for configuration in CONFIGURATIONS {
+      match qualified {
+        true => parser.start_configuration(&format!(":{}:{}", project.artifact, configuration)),
+        false => parser.start_configuration(configuration),
+      }
+
+      /* depth below which modules are skipped, because their parent is not on the configuration */
+      let mut skip_below: Option<usize> = None;
+
+      for node in project.nodes.iter() {
+        if skip_below.is_some_and(|depth| node.depth > depth) {
+          continue;
+        }
+
+        if !configurations_of(&node.scope).contains(&configuration) {
+          skip_below = Some(node.depth);
+          continue;
+        }
+        skip_below = None;
+
+        parser.add_dependency(
+          node.depth,
+          node.repeated,
+          ParsedDependency {
+            name: node.name.clone(),
+            namespace: node.namespace.clone(),
+            transitive: Version::Transitive(node.requested.clone().unwrap_or(node.version.clone())),
+            pinned: match &node.requested {
+              Some(_) => Version::Pinned(node.version.clone()),
+              None => Version::NotApplicable,
+            },
+          },
+        );
+      }
+    }
+  }
+
+  parser.end_input();
+}
+
+fn configurations_of(scope: &str) -> &'static [&'static str] {
+  SCOPE_CONFIGURATIONS
+    .iter()
+    .find(|(s, _)| *s == scope)
+    .map_or(&[], |(_, configurations)| configurations)
+}
+
+fn parse_projects<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<MavenProject> {
+  let mut projects: Vec<MavenProject> = Vec::new();
+
+  for line in lines {
+    let line = line.strip_prefix(LOG_PREFIX).unwrap_or(line);
+    let line = line.strip_prefix(' ').unwrap_or(line).trim_end();
+
+    match tree_marker(line) {
+      Some(idx) => {
+        if let (Some(project), Some(node)) = (projects.last_mut(), parse_node(idx / INDENT, &line[idx + 3..])) {
+          project.nodes.push(node);
+        }
+      }
+      /* `group:artifact:packaging:version` of the project the tree is of */
+      None if !line.contains(' ') && line.split(':').count() == 4 => projects.push(MavenProject {
+        artifact: line.split(':').nth(1).unwrap().to_string(),
+        nodes: Vec::new(),
+      }),
+      None => (),
+    }
+  }
+
+  projects
+}
+
+/// Position of the `+- `/`\- ` marker of a tree line, preceded by one `|  ` or `   ` per level.
+fn tree_marker(line: &str) -> Option<usize> {
+  let mut idx = 0;
+
+  while idx + INDENT <= line.len() {
+    let segment = &line[idx..idx + INDENT];
+    if TREE_MARKERS.contains(&segment) {
+      return Some(idx);
+    }
+    if segment != "|  " && segment != "   " {
+      return None;
+    }
+    idx += INDENT;
+  }
+
+  None
+}
+
+/// Parses the module of a tree line, like `org.yaml:snakeyaml:jar:2.0:compile (version managed from 1.33)`
+/// or, for modules omitted by `-Dverbose`, `(org.yaml:snakeyaml:jar:1.33:compile - omitted for conflict with 2.0)`.
+fn parse_node(depth: usize, module: &str) -> Option<MavenNode> {
+  let (module, annotation, omitted) = match module
+    .strip_prefix('(')
+    .and_then(|m| m.strip_suffix(')'))
+  {
+    Some(m) => match m.split_once(" - ") {
+      Some((m, annotation)) => (m, Some(annotation), true),
+      None => (m, None, true),
+    },
+    None => match module.split_once(" (") {
+      Some((m, annotation)) => (m, annotation.strip_suffix(')'), false),
+      None => (module, None, false),
+    },
+  };
+
+  /* group:artifact:type[:classifier]:version:scope */
+  let parts: Vec<&str> = module.split(':').collect();
+  if parts.len() < 5 || parts.len() > 6 {
+    return None;
+  }
+  let (version, scope) = (parts[parts.len() - 2], parts[parts.len() - 1]);
+
+  /* several annotations are separated by `; `, e.g. `version managed from 1.33; scope managed from runtime` */
+  let mut annotations = annotation.into_iter().flat_map(|a| a.split("; "));
+  if annotations
+    .clone()
+    .any(|a| a.starts_with("omitted for cycle"))
+  {
+    return None;
+  }
+  let conflict = annotations
+    .clone()
+    .find_map(|a| a.strip_prefix("omitted for conflict with "));
+  let managed = annotations.find_map(|a| a.strip_prefix("version managed from "));
+  let (requested, version) = match (conflict, managed) {
+    (Some(winner), _) => (Some(version), winner),
+    (None, Some(managed)) => (Some(managed), version),
+    (None, None) => (None, version),
+  };
+
+  Some(MavenNode {
+    depth,
+    namespace: parts[0].to_string(),
+    name: parts[1].to_string(),
+    requested: requested.map(str::to_string),
+    version: version.to_string(),
+    scope: scope.to_string(),
+    repeated: omitted,
+  })
+}
+
+/// Checks whether a file is a `mvn dependency:tree` output rather than a Gradle one.
+pub fn is_maven_tree<P>(path: P) -> bool
+where
+  P: AsRef<Path>,
+{
+  let file = match fs::File::open(path) {
+    Ok(f) => f,
+    Err(_) => return false,
+  };
+
+  BufReader::new(file)
+    .lines()
+    .map_while(Result::ok)
+    .take(30)
+    .any(|l| {
+      let line = l.strip_prefix(LOG_PREFIX).unwrap_or(&l);
+      let line = line.strip_prefix(' ').unwrap_or(line);
+      line.contains(PLUGIN_IDENTIFIER) || tree_marker(line) == Some(0) && parse_node(0, &line[3..]).is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const TREE: [&str; 10] = [
+    "[INFO] --- maven-dependency-plugin:3.6.0:tree (default-cli) @ demo ---",
+    "[INFO] com.example:demo:jar:0.0.1-SNAPSHOT",
+    "[INFO] +- org.springframework.boot:spring-boot-starter:jar:3.1.4:compile",
+    "[INFO] |  +- org.yaml:snakeyaml:jar:2.0:compile (version managed from 1.33)",
+    "[INFO] |  \\- (org.springframework:spring-core:jar:6.0.11:compile - omitted for conflict with 6.0.12)",
+    "[INFO] +- org.postgresql:postgresql:jar:42.6.0:runtime",
+    "[INFO] |  \\- org.checkerframework:checker-qual:jar:3.31.0:runtime",
+    "[INFO] \\- org.junit.jupiter:junit-jupiter:jar:5.9.3:test",
+    "[INFO]    \\- org.junit.jupiter:junit-jupiter-api:jar:5.9.3:test",
+    "[INFO] ------------------------------------------------------------------------",
+  ];
+
+  #[test]
+  fn it_parses_modules_with_their_annotations() {
+    let projects = parse_projects(TREE.into_iter());
+
+    assert_eq!(projects.len(), 1);
+    assert_eq!(projects[0].artifact, "demo");
+    assert_eq!(projects[0].nodes.len(), 7);
+
+    let snakeyaml = &projects[0].nodes[1];
+    assert_eq!((snakeyaml.depth, snakeyaml.name.as_str()), (1, "snakeyaml"));
+    assert_eq!(snakeyaml.requested.as_deref(), Some("1.33"));
+    assert_eq!(snakeyaml.version, "2.0");
+
+    let spring_core = &projects[0].nodes[2];
+    assert!(spring_core.repeated);
+    assert_eq!(spring_core.requested.as_deref(), Some("6.0.11"));
+    assert_eq!(spring_core.version, "6.0.12");
+
+    assert_eq!(projects[0].nodes[6].depth, 1);
+  }
+
+  #[test]
+  fn it_reads_versions_managed_along_with_other_annotations() {
+    let node = parse_node(
+      2,
+      "org.postgresql:postgresql:jar:42.6.0:compile (version managed from 42.5.4; scope managed from runtime)",
+    )
+    .unwrap();
+    assert_eq!(node.requested.as_deref(), Some("42.5.4"));
+    assert_eq!((node.version.as_str(), node.scope.as_str()), ("42.6.0", "compile"));
+
+    let node = parse_node(
+      2,
+      "(org.yaml:snakeyaml:jar:1.33:compile - version managed from 1.30; omitted for conflict with 2.0)",
+    )
+    .unwrap();
+    assert!(node.repeated);
+    assert_eq!(node.requested.as_deref(), Some("1.33"));
+    assert_eq!(node.version, "2.0");
+  }
+
+  #[test]
+  fn it_maps_scopes_onto_configurations() {
+    let mut parser = DependencyParser::new();
+    parse(&mut parser, TREE.into_iter());
+
+    let configs = |coordinate: &str| -> Vec<String> {
+      parser.dep_maps[0][coordinate]
+        .gradle_entries
+        .iter()
+        .map(|e| e.gradle_config_name.clone())
+        .collect()
+    };
+    assert_eq!(
+      configs("org.postgresql:postgresql"),
+      vec!["runtimeClasspath", "testRuntimeClasspath"]
+    );
+    assert_eq!(
+      configs("org.junit.jupiter:junit-jupiter-api"),
+      vec!["testCompileClasspath", "testRuntimeClasspath"]
+    );
+
+    let compile = &parser.trees[0][0];
+    assert_eq!(compile.gradle_config_name, "compileClasspath");
+    assert_eq!(compile.roots.len(), 1);
+    assert_eq!(compile.roots[0].children.len(), 2);
+    assert_eq!(parser.trees[0][1].roots.len(), 2);
+  }
+}
@@ -0,0 +1,244 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BTreeMap;
+
+use crate::types::{
+  nullable, Acknowledged, ChangeKind, DependencyTree, GradleList, ProcessedDependencyObject, SemverLevel, TreeNode,
+};
+
+/// Key for `--group-by` changes whose root dependency can't be found, e.g. read from a snapshot.
+const UNKNOWN_ROOT: &str = "(unknown)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum SortBy {
+  /// Group, artifact, then configuration
+  #[default]
+  Name,
+  /// Kind of change (added, removed, upgraded, downgraded, changed, unchanged), then name
+  ChangeKind,
+  /// Most significant change first (major, minor, patch, other), then name
+  Impact,
+  /// Configuration, then name
+  Configuration,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "kebab-case")]
+pub enum GroupBy {
+  /// Group of the artifact
+  Group,
+  /// Gradle configuration
+  Configuration,
+  /// Direct dependency that brings the changed dependency in, read from the dependency trees
+  RootCause,
+}
+
+/// Changes sharing the same `--group-by` key.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ChangeGroup {
+  pub key: String,
+  pub changes: Vec<GroupedChange>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct GroupedChange {
+  pub coordinate: String,
+  pub configuration: String,
+  #[serde(with = "nullable")]
+  #[schemars(with = "Option<String>")]
+  pub version_before: String,
+  #[serde(with = "nullable")]
+  #[schemars(with = "Option<String>")]
+  pub version_after: String,
+  pub kind: ChangeKind,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub acknowledged: Option<Acknowledged>,
+}
+
+/// Sorts the configurations of each dependency, then the dependencies by their first configuration,
+/// so that every output lists them in the same, deterministic order. \
+/// Ties are broken by `group:artifact` and configuration name.
+pub fn sort(values: &mut [ProcessedDependencyObject], sort_by: SortBy) {
+  for value in values.iter_mut() {
+    value
+      .gradle_versions
+      .sort_by(|a, b| compare_lists(a, b, sort_by));
+  }
+
+  values.sort_by(|a, b| {
+    let by_first = match (a.gradle_versions.first(), b.gradle_versions.first()) {
+      (Some(a), Some(b)) => compare_keys(a, b, sort_by),
+      (a, b) => a.is_none().cmp(&b.is_none()),
+    };
+
+    by_first
+      .then_with(|| a.dependency.namespace.cmp(&b.dependency.namespace))
+      .then_with(|| a.dependency.name.cmp(&b.dependency.name))
+  });
+}
+
+fn compare_lists(a: &GradleList, b: &GradleList, sort_by: SortBy) -> Ordering {
+  compare_keys(a, b, sort_by).then_with(|| a.gradle_config_name.cmp(&b.gradle_config_name))
+}
+
+fn compare_keys(a: &GradleList, b: &GradleList, sort_by: SortBy) -> Ordering {
+  match sort_by {
+    SortBy::Name => Ordering::Equal,
+    SortBy::Configuration => a.gradle_config_name.cmp(&b.gradle_config_name),
+    SortBy::ChangeKind => a.change_kind().cmp(&b.change_kind()),
+    SortBy::Impact => Reverse(impact(a)).cmp(&Reverse(impact(b))),
+  }
+}
+
+/// Significance of a change, a dependency appearing or disappearing counting as `Major`.
+fn impact(list: &GradleList) -> SemverLevel {
+  match list.change_kind() {
+    ChangeKind::Added | ChangeKind::Removed => SemverLevel::Major,
+    _ => SemverLevel::of(&list.version_before, &list.version_after),
+  }
+}
+
+/// Groups the changes of the (sorted) values by `group_by`, groups being sorted by key. \
+/// For `root-cause`, a change is grouped under the first root of its configuration's tree
+/// (after, or before for removals) that contains the dependency.
+pub fn group(
+  values: &[ProcessedDependencyObject],
+  group_by: GroupBy,
+  trees: &[Vec<DependencyTree>; 2],
+) -> Vec<ChangeGroup> {
+  let mut groups: BTreeMap<String, Vec<GroupedChange>> = BTreeMap::new();
+
+  for value in values.iter() {
+    let coordinate = format!("{}:{}", value.dependency.namespace, value.dependency.name);
+
+    for list in value.gradle_versions.iter() {
+      let kind = list.change_kind();
+      if !kind.is_change() {
+        continue;
+      }
+
+      let key = match group_by {
+        GroupBy::Group => value.dependency.namespace.clone(),
+        GroupBy::Configuration => list.gradle_config_name.clone(),
+        GroupBy::RootCause => {
+          let side = match kind {
+            ChangeKind::Removed => &trees[0],
+            _ => &trees[1],
+          };
+          root_cause(side, &list.gradle_config_name, &coordinate).unwrap_or_else(|| UNKNOWN_ROOT.to_string())
+        }
+      };
+
+      groups.entry(key).or_default().push(GroupedChange {
+        coordinate: coordinate.clone(),
+        configuration: list.gradle_config_name.clone(),
+        version_before: list.version_before.clone(),
+        version_after: list.version_after.clone(),
+        kind,
+        acknowledged: list.acknowledged.clone(),
+      });
+    }
+  }
+
+  groups
+    .into_iter()
+    .map(|(key, changes)| ChangeGroup { key, changes })
+    .collect()
+}
+
+fn root_cause(trees: &[DependencyTree], configuration: &str, coordinate: &str) -> Option<String> {
+  trees
+    .iter()
+    .find(|t| t.gradle_config_name == configuration)?
+    .roots
+    .iter()
+    .find(|root| contains(root, coordinate))
+    .map(|root| root.coordinate())
+}
+
+fn contains(node: &TreeNode, coordinate: &str) -> bool {
+  node.coordinate() == coordinate || node.children.iter().any(|c| contains(c, coordinate))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::{dep, node};
+
+  fn values() -> Vec<ProcessedDependencyObject> {
+    vec![
+      dep("org.a:patched", vec![("runtimeClasspath", "1.0.0", "1.0.1")]),
+      dep(
+        "org.b:major",
+        vec![
+          ("runtimeClasspath", "1.0.0", "2.0.0"),
+          ("compileClasspath", "1.0.0", "1.0.0"),
+        ],
+      ),
+      dep("org.c:added", vec![("annotationProcessor", "N/A", "1.0.0")]),
+    ]
+  }
+
+  fn coordinates(values: &[ProcessedDependencyObject]) -> Vec<String> {
+    values
+      .iter()
+      .map(|v| format!("{}:{}", v.dependency.namespace, v.dependency.name))
+      .collect()
+  }
+
+  #[test]
+  fn it_sorts_by_name_and_configuration_by_default() {
+    let mut values = values();
+    sort(&mut values, SortBy::default());
+
+    assert_eq!(
+      coordinates(&values),
+      vec!["org.a:patched", "org.b:major", "org.c:added"]
+    );
+    assert_eq!(values[1].gradle_versions[0].gradle_config_name, "compileClasspath");
+  }
+
+  #[test]
+  fn it_sorts_by_impact_change_kind_and_configuration() {
+    let mut values = values();
+
+    sort(&mut values, SortBy::Impact);
+    assert_eq!(
+      coordinates(&values),
+      vec!["org.b:major", "org.c:added", "org.a:patched"]
+    );
+    assert_eq!(values[0].gradle_versions[0].gradle_config_name, "runtimeClasspath");
+
+    sort(&mut values, SortBy::ChangeKind);
+    assert_eq!(
+      coordinates(&values),
+      vec!["org.c:added", "org.a:patched", "org.b:major"]
+    );
+
+    sort(&mut values, SortBy::Configuration);
+    assert_eq!(
+      coordinates(&values),
+      vec!["org.c:added", "org.b:major", "org.a:patched"]
+    );
+  }
+
+  #[test]
+  fn it_groups_changes_by_root_cause() {
+    let after = vec![DependencyTree {
+      gradle_config_name: "runtimeClasspath".to_string(),
+      roots: vec![
+        node("org.x:starter", "1.0.0", vec![node("org.a:patched", "1.0.0", vec![])]),
+        node("org.b:major", "1.0.0", vec![]),
+      ],
+    }];
+
+    let groups = group(&values(), GroupBy::RootCause, &[Vec::new(), after]);
+
+    let keys: Vec<&str> = groups.iter().map(|g| g.key.as_str()).collect();
+    assert_eq!(keys, vec!["(unknown)", "org.b:major", "org.x:starter"]);
+    assert_eq!(groups[2].changes[0].coordinate, "org.a:patched");
+    assert_eq!(groups[1].changes.len(), 1);
+  }
+}
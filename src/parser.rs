@@ -5,10 +5,17 @@ use std::io::BufRead;
 use std::io::BufReader;
 use version_compare::{compare_to, Cmp};
 
+use crate::lockfile;
 use crate::types::*;
 
 const ARROW: &str = "->";
 const ROOT_PROJECT_IDENTIFIER: &str = "Root project";
+const PROJECT_IDENTIFIER: &str = "Project ";
+const TASK_IDENTIFIER: &str = "> Task ";
+const BUILD_ENVIRONMENT_TASK: &str = ":buildEnvironment";
+/// Prefix of the configurations of the buildscript, to keep them apart from the project ones.
+pub const BUILDSCRIPT_NAMESPACE: &str = "buildscript.";
+const UNRESOLVABLE_MARKER: &str = "(n)";
 const VALID_DEP_LINE_START_CHARS: [char; 4] = [
   '\\', /* \--- com.h2database:h2 (n) */
   '+',  /* +--- org.openapitools:openapi-generator-gradle-plugin:7.0.1 */
@@ -18,10 +25,21 @@ const VALID_DEP_LINE_START_CHARS: [char; 4] = [
 
 pub struct DependencyParser {
   pub dep_maps: [Box<FxHashMap<String, Dependency>>; 2],
+  /// Dependency trees of each Gradle configuration, in the order they were parsed.
+  pub trees: [Vec<DependencyTree>; 2],
+  /// Configurations marked `(n)`, which Gradle can't resolve (e.g. `implementation`).
+  pub unresolvable_configurations: [FxHashSet<String>; 2],
   pub active_map: usize,
+  /// Configuration name -> name it is joined under in `compare_versions` and the trees are named after,
+  /// to compare differently named configurations. Set before parsing.
+  pub configuration_aliases: FxHashMap<String, String>,
   skip: usize,
   in_task: bool,
   found_root: bool,
+  /// Path of the project being parsed followed by `:`, empty for the root project.
+  curr_project: String,
+  /// Whether the output being parsed is the one of `buildEnvironment`.
+  build_environment: bool,
   curr_gradle_task: String,
 }
 
@@ -29,25 +47,76 @@ impl DependencyParser {
   pub fn new() -> Self {
     Self {
       dep_maps: [Box::new(FxHashMap::default()), Box::new(FxHashMap::default())],
+      trees: [Vec::new(), Vec::new()],
+      unresolvable_configurations: [FxHashSet::default(), FxHashSet::default()],
       active_map: 0,
+      configuration_aliases: FxHashMap::default(),
       skip: 0,
       in_task: false,
       found_root: false,
+      curr_project: String::new(),
+      build_environment: false,
       curr_gradle_task: String::new(),
     }
   }
 
   pub fn parse_file(&mut self, file: BufReader<fs::File>) {
+    /* each file has its own header, so don't carry state over from the previous one */
+    self.skip = 0;
+    self.in_task = false;
+    self.found_root = false;
+    self.curr_project.clear();
+    self.build_environment = false;
+    self.curr_gradle_task.clear();
+
     for line in file.lines().map(|l| l.unwrap()) {
-      match self.parse_line(line) {
-        LineParseResult::Parsed | LineParseResult::Skip => (),
-        LineParseResult::End => break,
-      }
+      self.parse_line(line);
     }
 
     self.active_map += 1;
   }
 
+  /// Uses an already parsed dependency map (e.g. read from a snapshot) as the next input.
+  pub fn load_dep_map(&mut self, dep_map: FxHashMap<String, Dependency>) {
+    *self.dep_maps[self.active_map] = dep_map;
+    self.active_map += 1;
+  }
+
+  /// Drops the configurations of an input that can't be in a lockfile, to diff it against one.
+  pub fn retain_lockable_configurations(&mut self, side: usize) {
+    let unresolvable = &self.unresolvable_configurations[side];
+
+    self.dep_maps[side].retain(|_, dep| {
+      dep
+        .gradle_entries
+        .retain(|e| lockfile::is_lockable(&e.gradle_config_name, unresolvable));
+      !dep.gradle_entries.is_empty()
+    });
+    self.trees[side].retain(|t| lockfile::is_lockable(&t.gradle_config_name, unresolvable));
+  }
+
+  /// Makes `gradle_config_name` the configuration the next dependencies are added to, with a new tree. \
+  /// Used by the parsers of other formats to build the same model.
+  pub fn start_configuration(&mut self, gradle_config_name: &str) {
+    self.curr_gradle_task = gradle_config_name.to_string();
+
+    self.trees[self.active_map].push(DependencyTree {
+      gradle_config_name: aliased(gradle_config_name, &self.configuration_aliases).to_string(),
+      roots: Vec::new(),
+    });
+  }
+
+  /// Adds a dependency of the current configuration, at `depth` of its tree.
+  pub fn add_dependency(&mut self, depth: usize, repeated: bool, dependency: ParsedDependency) {
+    self.add_tree_node(depth, repeated, &dependency);
+    self.add_or_update_dep(dependency);
+  }
+
+  /// Ends an input built with `start_configuration` and `add_dependency`.
+  pub fn end_input(&mut self) {
+    self.active_map += 1;
+  }
+
   fn parse_line(&mut self, line: String) -> LineParseResult {
     /* first check if skipping */
     if self.skip > 0 {
@@ -59,30 +128,43 @@ impl DependencyParser {
       return LineParseResult::Skip;
     }
 
-    /* skip non-root lines and empty lines */
-    if (!self.found_root && !line.starts_with(ROOT_PROJECT_IDENTIFIER)) || line.is_empty() {
+    if let Some(task) = line.strip_prefix(TASK_IDENTIFIER) {
+      self.build_environment = task.trim_end().ends_with(BUILD_ENVIRONMENT_TASK);
+      return LineParseResult::Skip;
+    }
+
+    let project = project_path(&line);
+
+    /* skip lines before a project header and empty lines */
+    if (!self.found_root && project.is_none()) || line.is_empty() {
       return LineParseResult::Skip;
     }
 
-    if line.starts_with(ROOT_PROJECT_IDENTIFIER) {
+    if let Some(project) = project {
       self.found_root = true;
+      self.in_task = false;
+      self.curr_project = project;
       self.skip = 2;
       return LineParseResult::Skip;
     }
 
-    /* declares end of parsing when encountering lines like
-    (c) - dependency constraint */
+    /* lines like `(c) - dependency constraint` end the report of a project,
+    multi-project outputs then continue with the header of the next one */
     if line.starts_with('(') {
-      return LineParseResult::End;
+      self.found_root = false;
+      self.in_task = false;
+      return LineParseResult::Skip;
     }
 
     if self.in_task {
       if VALID_DEP_LINE_START_CHARS.contains(&line.trim().chars().next().unwrap()) {
+        let depth = tree_depth(&line);
+        let repeated = line.ends_with("(*)");
         let dep_res = self.parse_dep_line(line);
 
         match dep_res {
           DepParseResult::Dep(dep_opt) => {
-            self.add_or_update_dep(dep_opt);
+            self.add_dependency(depth, repeated, dep_opt);
             return LineParseResult::Parsed;
           }
           DepParseResult::NoDependencies => {
@@ -92,16 +174,27 @@ impl DependencyParser {
         }
       } else {
         /* If in task block but line doesn't start with any of "\\, +, |, N",
-        line is the gradle task name. */
-        self.curr_gradle_task = match line.find(' ') {
-          Some(idx) => line[..idx].to_string(),
-          None => line,
+        line is the gradle task name, qualified by the path of subprojects.
+        The configurations of the `buildEnvironment` output get their own namespace, a `dependencies` output
+        can have a `classpath` configuration too. */
+        let name = match line.find(' ') {
+          Some(idx) => &line[..idx],
+          None => &line,
         };
+        let namespace = match self.build_environment {
+          true => BUILDSCRIPT_NAMESPACE,
+          false => "",
+        };
+        self.start_configuration(&format!("{}{}{}", self.curr_project, namespace, name));
+
+        if line.ends_with(UNRESOLVABLE_MARKER) {
+          self.unresolvable_configurations[self.active_map].insert(self.curr_gradle_task.clone());
+        }
       }
 
       return LineParseResult::Skip;
     }
-    return LineParseResult::Skip;
+    LineParseResult::Skip
   }
 
   fn parse_dep_line(&self, line: String) -> DepParseResult {
@@ -180,16 +273,48 @@ impl DependencyParser {
 
     let namespace = line[namespace_start..name_start].to_string();
 
-    return DepParseResult::Dep(ParsedDependency {
+    DepParseResult::Dep(ParsedDependency {
       name: line[(name_start + 1)..name_end].to_string(),
-      namespace: namespace,
+      namespace,
       transitive: ver_transitive,
       pinned: ver_pinned,
+    })
+  }
+
+  /// Appends a node to the tree of the current Gradle task, as the last child
+  /// of the last node at `depth - 1`.
+  fn add_tree_node(&mut self, depth: usize, repeated: bool, dependency: &ParsedDependency) {
+    let tree = match self.trees[self.active_map].last_mut() {
+      Some(tree) => tree,
+      None => return,
+    };
+
+    let version = match (dependency.pinned.is_applicable(), dependency.transitive.is_applicable()) {
+      (true, _) => dependency.pinned.to_string(),
+      (false, true) => dependency.transitive.to_string(),
+      (false, false) => "N/A".to_string(),
+    };
+
+    let mut siblings = &mut tree.roots;
+    for _ in 0..depth {
+      /* a malformed, too deep line is attached to the deepest available parent */
+      if siblings.is_empty() {
+        break;
+      }
+      siblings = &mut siblings.last_mut().unwrap().children;
+    }
+
+    siblings.push(TreeNode {
+      name: dependency.name.clone(),
+      namespace: dependency.namespace.clone(),
+      version,
+      repeated,
+      children: Vec::new(),
     });
   }
 
   fn add_or_update_dep(&mut self, dependency: ParsedDependency) {
-    if self.dep_maps[self.active_map].contains_key(&dependency.name) {
+    if self.dep_maps[self.active_map].contains_key(&dependency.coordinate()) {
       self.update_dep(dependency);
     } else {
       self.add_dep(dependency);
@@ -197,6 +322,7 @@ impl DependencyParser {
   }
 
   fn add_dep(&mut self, dependency: ParsedDependency) {
+    let coordinate = dependency.coordinate();
     let dep_entry = Dependency {
       name: dependency.name.clone(),
       namespace: dependency.namespace.clone(),
@@ -209,12 +335,13 @@ impl DependencyParser {
       }],
     };
 
-    self.dep_maps[self.active_map].insert(dependency.name.clone(), dep_entry);
+    self.dep_maps[self.active_map].insert(coordinate, dep_entry);
   }
 
   fn update_dep(&mut self, dependency: ParsedDependency) {
+    let coordinate = dependency.coordinate();
     let mut existing = self.dep_maps[self.active_map]
-      .get(&dependency.name)
+      .get(&coordinate)
       .unwrap()
       .clone();
 
@@ -241,7 +368,7 @@ impl DependencyParser {
       }
     }
 
-    self.dep_maps[self.active_map].insert(dependency.name, existing);
+    self.dep_maps[self.active_map].insert(coordinate, existing);
   }
 
   fn update_existing(&self, existing: &mut GradleEntry, new: &ParsedDependency) {
@@ -260,13 +387,11 @@ impl DependencyParser {
 
     /* if newly parsed transitive value isn't "N/A" and
     is not already in the array, add it */
-    if new.transitive.is_applicable() {
-      if !existing.versions.transitive_contains(&new.transitive) {
-        existing
-          .versions
-          .transitive
-          .push(new.transitive.clone());
-      }
+    if new.transitive.is_applicable() && !existing.versions.transitive_contains(&new.transitive) {
+      existing
+        .versions
+        .transitive
+        .push(new.transitive.clone());
     }
   }
 
@@ -287,16 +412,15 @@ impl DependencyParser {
         common.insert(k.clone());
       }
 
-      let gradle_lists = create_gradle_lists(Option::Some(v), value_after);
+      let gradle_lists = create_gradle_lists(Option::Some(v), value_after, &self.configuration_aliases);
 
       let changed = gradle_lists
         .iter()
         .any(|el| el.version_before != el.version_after);
 
-      let entries_after = if value_after.is_some() {
-        value_after.unwrap().gradle_entries.clone()
-      } else {
-        Vec::new()
+      let entries_after = match value_after {
+        Some(after) => after.gradle_entries.clone(),
+        None => Vec::new(),
       };
 
       processed.push(ProcessedDependencyObject {
@@ -307,16 +431,16 @@ impl DependencyParser {
           gradle_entries_after: entries_after,
         },
         gradle_versions: gradle_lists,
-        changed: changed,
+        changed,
       });
     });
 
     /* Add dependencies that are unique to second map */
     self.dep_maps[1]
       .iter()
-      .filter(|(k, _)| !common.contains(&k as &String))
+      .filter(|(k, _)| !common.contains(k as &String))
       .for_each(|(_, v)| {
-        let gradle_lists = create_gradle_lists(Option::None, Option::Some(v));
+        let gradle_lists = create_gradle_lists(Option::None, Option::Some(v), &self.configuration_aliases);
 
         let changed = gradle_lists
           .iter()
@@ -330,11 +454,46 @@ impl DependencyParser {
             gradle_entries_after: v.gradle_entries.clone(),
           },
           gradle_versions: gradle_lists,
-          changed: changed,
+          changed,
         });
       });
 
-    return processed;
+    processed
+  }
+
+  /// Resolved version of each dependency of one input in each of its configurations,
+  /// keyed by `group:artifact` and sorted by it.
+  pub fn resolved_versions(&self, side: usize) -> Vec<(String, Vec<TempList>)> {
+    let mut res: Vec<(String, Vec<TempList>)> = self.dep_maps[side]
+      .values()
+      .map(|v| (v.coordinate(), get_versions(v, &self.configuration_aliases)))
+      .collect();
+
+    res.sort_by(|a, b| a.0.cmp(&b.0));
+    res
+  }
+}
+
+/// Path of the project whose report starts at this header line followed by `:` (e.g. `:service-a:`
+/// for `Project ':service-a'` or `Project ':service-a' - Payments service`), empty for the root project,
+/// or `None` for other lines.
+fn project_path(line: &str) -> Option<String> {
+  if line.starts_with(ROOT_PROJECT_IDENTIFIER) {
+    return Some(String::new());
+  }
+
+  /* the path is quoted, and followed by the description of the project if it has one */
+  line
+    .strip_prefix(PROJECT_IDENTIFIER)
+    .map(|p| format!("{}:", p.split('\'').nth(1).unwrap_or(p.trim())))
+}
+
+/// Depth of a dependency line in its tree, from its `+--- `/`\--- ` prefix.
+/// Every level of nesting is indented by 5 characters (`|    `).
+fn tree_depth(line: &str) -> usize {
+  match line.find("--- ") {
+    Some(idx) => idx.saturating_sub(1) / 5,
+    None => 0,
   }
 }
 
@@ -367,7 +526,9 @@ fn get_greatest(arr: &[Version]) -> Option<String> {
 /// If both dependencies are provided, does join of gradle tasks and versions. e.g.:
 /// * x tasks: `['compileClasspath', 'compileJava']`
 /// * y tasks: `['annotationClasspath', 'compileJava']`
-/// * join   : `['compileClasspath', 'compileJava', 'annotationClasspath']`. \
+/// * join   : `['compileClasspath', 'compileJava', 'annotationClasspath']`.
+///
+/// Tasks are joined under their alias in `aliases`, if any. \
 /// For the unique tasks, value of other is `"N/A"`. \
 /// If only one is provided, makes a vector with `before` or `after` for the missing one
 /// having the value `"N/A"`.
@@ -382,15 +543,19 @@ fn get_greatest(arr: &[Version]) -> Option<String> {
 /// ],
 /// ...
 /// ```
-fn create_gradle_lists(before: Option<&Dependency>, after: Option<&Dependency>) -> Vec<GradleList> {
+fn create_gradle_lists(
+  before: Option<&Dependency>,
+  after: Option<&Dependency>,
+  aliases: &FxHashMap<String, String>,
+) -> Vec<GradleList> {
   let mut res: Vec<GradleList> = Vec::new();
 
-  if before.is_some() && after.is_some() {
+  if let (Some(before), Some(after)) = (before, after) {
     /* keep already encountered Gradle tasks here */
     let mut done: FxHashSet<String> = FxHashSet::default();
 
-    let ver_before = get_versions(before.unwrap());
-    let ver_after = get_versions(after.unwrap());
+    let ver_before = get_versions(before, aliases);
+    let ver_after = get_versions(after, aliases);
 
     /* cover tasks before */
     ver_before.iter().for_each(|t_b| {
@@ -401,11 +566,7 @@ fn create_gradle_lists(before: Option<&Dependency>, after: Option<&Dependency>)
           found = true;
           done.insert(t_b.gradle_config_name.clone());
 
-          res.push(GradleList {
-            gradle_config_name: t_b.gradle_config_name.clone(),
-            version_before: t_b.version.clone(),
-            version_after: t_a.version.clone(),
-          });
+          res.push(GradleList::new(&t_b.gradle_config_name, &t_b.version, &t_a.version));
 
           break;
         }
@@ -413,11 +574,7 @@ fn create_gradle_lists(before: Option<&Dependency>, after: Option<&Dependency>)
 
       /* if not found, means it doesn't exist in other dep */
       if !found {
-        res.push(GradleList {
-          gradle_config_name: t_b.gradle_config_name.clone(),
-          version_before: t_b.version.clone(),
-          version_after: "N/A".to_string(),
-        })
+        res.push(GradleList::new(&t_b.gradle_config_name, &t_b.version, "N/A"))
       }
     });
 
@@ -425,36 +582,29 @@ fn create_gradle_lists(before: Option<&Dependency>, after: Option<&Dependency>)
     ver_after
       .iter()
       .filter(|t| !done.contains(&t.gradle_config_name))
-      .for_each(|t| {
-        res.push(GradleList {
-          gradle_config_name: t.gradle_config_name.clone(),
-          version_before: "N/A".to_string(),
-          version_after: t.version.clone(),
-        })
-      });
-  } else if before.is_some() {
-    let ver_before = get_versions(before.unwrap());
-
-    ver_before.iter().for_each(|t| {
-      res.push(GradleList {
-        gradle_config_name: t.gradle_config_name.clone(),
-        version_before: t.version.clone(),
-        version_after: "N/A".to_string(),
-      })
-    });
-  } else if after.is_some() {
-    let ver_after = get_versions(after.unwrap());
-
-    ver_after.iter().for_each(|t| {
-      res.push(GradleList {
-        gradle_config_name: t.gradle_config_name.clone(),
-        version_before: "N/A".to_string(),
-        version_after: t.version.clone(),
-      })
-    });
+      .for_each(|t| res.push(GradleList::new(&t.gradle_config_name, "N/A", &t.version)));
+  } else if let Some(before) = before {
+    let ver_before = get_versions(before, aliases);
+
+    ver_before
+      .iter()
+      .for_each(|t| res.push(GradleList::new(&t.gradle_config_name, &t.version, "N/A")));
+  } else if let Some(after) = after {
+    let ver_after = get_versions(after, aliases);
+
+    ver_after
+      .iter()
+      .for_each(|t| res.push(GradleList::new(&t.gradle_config_name, "N/A", &t.version)));
   }
 
-  return res;
+  res
+}
+
+/// Name the configuration `gradle_config_name` is compared as, given the `--alias-configuration` aliases.
+pub fn aliased<'a>(gradle_config_name: &'a str, aliases: &'a FxHashMap<String, String>) -> &'a str {
+  aliases
+    .get(gradle_config_name)
+    .map_or(gradle_config_name, String::as_str)
 }
 
 /// For each Gradle task of the dependency, checks transitive and pinned before,
@@ -462,8 +612,8 @@ fn create_gradle_lists(before: Option<&Dependency>, after: Option<&Dependency>)
 /// * If it has no pinned, means transitive is valid. \
 /// * If it has no transitive, means pinned is valid. \
 /// * Else (if both transitive and pinned exist), pinned is going to be the
-/// active version in the Gradle task.
-fn get_versions(element: &Dependency) -> Vec<TempList> {
+///   active version in the Gradle task.
+fn get_versions(element: &Dependency, aliases: &FxHashMap<String, String>) -> Vec<TempList> {
   let mut versions_for_each_config: Vec<TempList> = Vec::new();
 
   for entry in element.gradle_entries.iter() {
@@ -478,13 +628,27 @@ fn get_versions(element: &Dependency) -> Vec<TempList> {
       },
     };
 
-    versions_for_each_config.push(TempList {
-      gradle_config_name: entry.gradle_config_name.clone(),
-      version: ver,
-    });
+    let gradle_config_name = aliased(&entry.gradle_config_name, aliases);
+
+    /* an alias target can exist next to its source, then both are merged into the greatest version */
+    match versions_for_each_config
+      .iter_mut()
+      .find(|t| t.gradle_config_name == gradle_config_name)
+    {
+      Some(existing) if existing.version == "N/A" => existing.version = ver,
+      Some(existing) if ver != "N/A" => {
+        existing.version =
+          get_greatest(&[Version::Transitive(existing.version.clone()), Version::Transitive(ver)]).unwrap()
+      }
+      Some(_) => (),
+      None => versions_for_each_config.push(TempList {
+        gradle_config_name: gradle_config_name.to_string(),
+        version: ver,
+      }),
+    }
   }
 
-  return versions_for_each_config;
+  versions_for_each_config
 }
 
 #[cfg(test)]
@@ -647,7 +811,11 @@ mod tests {
       .to_vec(),
     };
 
-    let list = create_gradle_lists(Option::Some(&dep_before), Option::Some(&dep_after));
+    let list = create_gradle_lists(
+      Option::Some(&dep_before),
+      Option::Some(&dep_after),
+      &FxHashMap::default(),
+    );
 
     println!("{}", serde_json::to_string_pretty(&list).unwrap());
 
@@ -689,7 +857,7 @@ mod tests {
       .to_vec(),
     };
 
-    let list = create_gradle_lists(Option::Some(&dep_before), Option::None);
+    let list = create_gradle_lists(Option::Some(&dep_before), Option::None, &FxHashMap::default());
 
     println!("{}", serde_json::to_string_pretty(&list).unwrap());
 
@@ -736,7 +904,7 @@ mod tests {
       .to_vec(),
     };
 
-    let list = create_gradle_lists(Option::None, Option::Some(&dep_after));
+    let list = create_gradle_lists(Option::None, Option::Some(&dep_after), &FxHashMap::default());
 
     println!("{}", serde_json::to_string_pretty(&list).unwrap());
 
@@ -755,6 +923,48 @@ mod tests {
       .for_each(|t| assert!(t.version_before == "N/A"))
   }
 
+  #[test]
+  fn it_joins_aliased_gradle_tasks() {
+    let entry = |config: &str, version: &str| GradleEntry {
+      gradle_config_name: config.to_string(),
+      versions: Versions {
+        transitive: vec![Version::Transitive(version.to_string())],
+        pinned: Version::NotApplicable,
+      },
+    };
+    let dep = |entries: Vec<GradleEntry>| Dependency {
+      name: "dep".to_string(),
+      namespace: "dep_ns".to_string(),
+      gradle_entries: entries,
+    };
+
+    let dep_before = dep(vec![entry("runtimeClasspath", "1.0.0")]);
+    let dep_after = dep(vec![entry("releaseRuntimeClasspath", "1.1.0")]);
+
+    let mut aliases: FxHashMap<String, String> = FxHashMap::default();
+    aliases.insert("releaseRuntimeClasspath".to_string(), "runtimeClasspath".to_string());
+
+    let list = create_gradle_lists(Option::Some(&dep_before), Option::Some(&dep_after), &aliases);
+
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].gradle_config_name, "runtimeClasspath");
+    assert_eq!(list[0].version_before, "1.0.0");
+    assert_eq!(list[0].version_after, "1.1.0");
+
+    /* both the alias and its target exist on each side */
+    let dep_both = dep(vec![
+      entry("runtimeClasspath", "1.0.0"),
+      entry("releaseRuntimeClasspath", "2.0.0"),
+    ]);
+
+    let list = create_gradle_lists(Option::Some(&dep_both), Option::Some(&dep_both), &aliases);
+
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0].gradle_config_name, "runtimeClasspath");
+    assert_eq!(list[0].version_before, "2.0.0");
+    assert_eq!(list[0].version_after, "2.0.0");
+  }
+
   #[test]
   // TODO: Convert this to an integration test
   fn it_updates_existing_pinned_version_if_is_greater() {
@@ -767,16 +977,222 @@ mod tests {
     parser.parse_line(String::from("| io.github.openfeign:feign-core:4.0.3 -> 4.0.5"));
 
     let dep = parser.dep_maps[0]
-      .get("feign-core")
+      .get("io.github.openfeign:feign-core")
       .expect("Dependency should exist");
 
     let gradle_entry: &GradleEntry = &dep
       .gradle_entries
       .iter()
+      .filter(|&e| e.gradle_config_name == "compileClasspath")
       .cloned()
-      .filter(|e| e.gradle_config_name == "compileClasspath")
       .collect::<Vec<GradleEntry>>()[0];
 
     assert_eq!(gradle_entry.versions.pinned.to_string(), "4.0.5");
   }
+
+  #[test]
+  fn it_builds_the_dependency_tree_of_each_task() {
+    let mut parser = DependencyParser::new();
+    parser.found_root = true;
+    parser.in_task = true;
+
+    [
+      "runtimeClasspath - Runtime classpath of source set 'main'.",
+      "+--- org.springframework.boot:spring-boot-starter -> 3.1.4",
+      "|    +--- org.springframework.boot:spring-boot:3.1.4",
+      "|    |    \\--- org.springframework:spring-core:6.0.12",
+      "|    \\--- org.yaml:snakeyaml:2.0",
+      "\\--- org.springframework:spring-core:6.0.11 -> 6.0.12 (*)",
+    ]
+    .iter()
+    .for_each(|l| {
+      parser.parse_line(l.to_string());
+    });
+
+    parser.parse_line("implementation - Implementation dependencies for the 'main' feature. (n)".to_string());
+
+    let tree = &parser.trees[0][0];
+    assert_eq!(tree.gradle_config_name, "runtimeClasspath");
+    assert_eq!(tree.roots.len(), 2);
+    assert!(parser.unresolvable_configurations[0].contains("implementation"));
+    assert!(!parser.unresolvable_configurations[0].contains("runtimeClasspath"));
+
+    let starter = &tree.roots[0];
+    assert_eq!(starter.version, "3.1.4");
+    assert_eq!(starter.children.len(), 2);
+    assert_eq!(
+      starter.children[0].children[0].coordinate(),
+      "org.springframework:spring-core"
+    );
+    assert_eq!(starter.children[1].name, "snakeyaml");
+
+    assert_eq!(tree.roots[1].version, "6.0.12");
+    assert!(tree.roots[1].repeated);
+  }
+
+  #[test]
+  fn it_qualifies_the_gradle_tasks_of_subprojects() {
+    let mut parser = DependencyParser::new();
+
+    [
+      "> Task :dependencies",
+      "------------------------------------------------------------",
+      "Root project 'demo'",
+      "------------------------------------------------------------",
+      "",
+      "runtimeClasspath - Runtime classpath of source set 'main'.",
+      "\\--- io.netty:netty-handler:4.1.100.Final",
+      "",
+      "(*) - Indicates repeated occurrences of a transitive dependency subtree.",
+      "",
+      "> Task :service-a:dependencies",
+      "------------------------------------------------------------",
+      "Project ':service-a'",
+      "------------------------------------------------------------",
+      "",
+      "runtimeClasspath - Runtime classpath of source set 'main'.",
+      "\\--- io.netty:netty-handler:4.1.94.Final",
+    ]
+    .iter()
+    .for_each(|l| {
+      parser.parse_line(l.to_string());
+    });
+
+    let entries = &parser.dep_maps[0]["io.netty:netty-handler"].gradle_entries;
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].gradle_config_name, "runtimeClasspath");
+    assert_eq!(entries[1].gradle_config_name, ":service-a:runtimeClasspath");
+    assert_eq!(parser.trees[0][1].gradle_config_name, ":service-a:runtimeClasspath");
+  }
+
+  #[test]
+  fn it_diffs_a_written_lockfile_against_its_output_without_changes() {
+    let output = [
+      "> Task :dependencies",
+      "------------------------------------------------------------",
+      "Root project 'demo'",
+      "------------------------------------------------------------",
+      "",
+      "compileClasspath - Compile classpath for source set 'main'.",
+      "\\--- com.google.guava:guava:32.1.2-jre",
+      "     \\--- com.google.guava:failureaccess:1.0.1",
+      "",
+      "implementation - Implementation dependencies for the 'main' feature. (n)",
+      "\\--- com.google.guava:guava:32.1.2-jre (n)",
+      "",
+      "runtimeClasspath - Runtime classpath of source set 'main'.",
+      "\\--- com.google.guava:guava:32.1.2-jre",
+      "",
+      "(n) - A dependency or dependency configuration that cannot be resolved.",
+    ];
+    let parse = |parser: &mut DependencyParser| {
+      output.iter().for_each(|l| {
+        parser.parse_line(l.to_string());
+      });
+      parser.active_map += 1;
+    };
+
+    let mut source = DependencyParser::new();
+    parse(&mut source);
+    let configurations: Vec<String> = source.trees[0]
+      .iter()
+      .map(|t| t.gradle_config_name.clone())
+      .filter(|c| lockfile::is_lockable(c, &source.unresolvable_configurations[0]))
+      .collect();
+    let written = lockfile::write(&source.resolved_versions(0), &configurations);
+
+    let mut parser = DependencyParser::new();
+    parser.load_dep_map(lockfile::parse(&written).unwrap());
+    parse(&mut parser);
+    parser.retain_lockable_configurations(1);
+
+    let values = parser.compare_versions();
+
+    assert_eq!(values.len(), 2);
+    assert!(values.iter().all(|v| !v.changed));
+    assert!(parser.trees[1]
+      .iter()
+      .all(|t| t.gradle_config_name != "implementation"));
+  }
+
+  #[test]
+  fn it_reads_the_path_of_project_headers() {
+    assert_eq!(project_path("Root project 'demo' - Demo service"), Some(String::new()));
+    assert_eq!(project_path("Project ':service-a'"), Some(":service-a:".to_string()));
+    assert_eq!(
+      project_path("Project ':libs:core' - Payments service"),
+      Some(":libs:core:".to_string())
+    );
+    assert_eq!(
+      project_path("runtimeClasspath - Runtime classpath of source set 'main'."),
+      None
+    );
+  }
+
+  #[test]
+  fn it_namespaces_the_buildscript_classpath() {
+    let mut parser = DependencyParser::new();
+
+    [
+      "> Task :buildEnvironment",
+      "",
+      "------------------------------------------------------------",
+      "Root project 'demo'",
+      "------------------------------------------------------------",
+      "",
+      "classpath",
+      "\\--- org.springframework.boot:org.springframework.boot.gradle.plugin:3.1.4",
+      "     \\--- org.springframework.boot:spring-boot-gradle-plugin:3.1.4",
+    ]
+    .iter()
+    .for_each(|l| {
+      parser.parse_line(l.to_string());
+    });
+
+    let entries = &parser.dep_maps[0]["org.springframework.boot:org.springframework.boot.gradle.plugin"].gradle_entries;
+    assert_eq!(entries[0].gradle_config_name, "buildscript.classpath");
+    assert_eq!(parser.trees[0][0].roots[0].children.len(), 1);
+
+    [
+      "> Task :dependencies",
+      "",
+      "------------------------------------------------------------",
+      "Root project 'demo'",
+      "------------------------------------------------------------",
+      "",
+      "classpath",
+      "\\--- org.yaml:snakeyaml:2.0",
+    ]
+    .iter()
+    .for_each(|l| {
+      parser.parse_line(l.to_string());
+    });
+
+    let entries = &parser.dep_maps[0]["org.yaml:snakeyaml"].gradle_entries;
+    assert_eq!(entries[0].gradle_config_name, "classpath");
+  }
+
+  #[test]
+  fn it_names_the_trees_after_the_configuration_aliases() {
+    let mut parser = DependencyParser::new();
+    parser
+      .configuration_aliases
+      .insert("productionRuntimeClasspath".to_string(), "runtimeClasspath".to_string());
+
+    parser.start_configuration("productionRuntimeClasspath");
+    parser.add_dependency(
+      0,
+      false,
+      ParsedDependency {
+        name: "snakeyaml".to_string(),
+        namespace: "org.yaml".to_string(),
+        transitive: Version::Transitive("2.0".to_string()),
+        pinned: Version::default(),
+      },
+    );
+
+    assert_eq!(parser.trees[0][0].gradle_config_name, "runtimeClasspath");
+    let entries = &parser.dep_maps[0]["org.yaml:snakeyaml"].gradle_entries;
+    assert_eq!(entries[0].gradle_config_name, "productionRuntimeClasspath");
+  }
 }
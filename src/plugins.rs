@@ -0,0 +1,83 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::types::{not_applicable, nullable, ChangeKind, ProcessedDependencyObject};
+
+/// Suffix of the artifact of plugin markers, named `<plugin id>.gradle.plugin` in the group `<plugin id>`.
+const PLUGIN_MARKER_SUFFIX: &str = ".gradle.plugin";
+
+/// Change of a plugin applied through the buildscript classpath, read from its plugin marker.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PluginChange {
+  /// Plugin id, e.g. `org.springframework.boot`.
+  pub id: String,
+  pub gradle_config_name: String,
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
+  pub version_before: String,
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
+  pub version_after: String,
+  pub kind: ChangeKind,
+}
+
+/// Changes of the plugin markers of the diff, in the order of `values`.
+pub fn changes(values: &[ProcessedDependencyObject]) -> Vec<PluginChange> {
+  values
+    .iter()
+    .filter_map(|v| {
+      v.dependency
+        .name
+        .strip_suffix(PLUGIN_MARKER_SUFFIX)
+        .map(|id| (id, v))
+    })
+    .flat_map(|(id, v)| {
+      v.gradle_versions
+        .iter()
+        .filter(|l| l.change_kind().is_change())
+        .map(move |l| PluginChange {
+          id: id.to_string(),
+          gradle_config_name: l.gradle_config_name.clone(),
+          version_before: l.version_before.clone(),
+          version_after: l.version_after.clone(),
+          kind: l.change_kind(),
+        })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::dep;
+
+  #[test]
+  fn it_reports_the_changes_of_plugin_markers() {
+    let values = vec![
+      dep(
+        "org.springframework.boot:org.springframework.boot.gradle.plugin",
+        vec![("buildscript.classpath", "3.1.3", "3.1.4")],
+      ),
+      dep(
+        "io.spring.dependency-management:io.spring.dependency-management.gradle.plugin",
+        vec![("buildscript.classpath", "1.1.3", "1.1.3")],
+      ),
+      dep(
+        "org.springframework.boot:spring-boot-gradle-plugin",
+        vec![("buildscript.classpath", "3.1.3", "3.1.4")],
+      ),
+      dep(
+        "com.diffplug.spotless:com.diffplug.spotless.gradle.plugin",
+        vec![("buildscript.classpath", "N/A", "6.22.0")],
+      ),
+    ];
+
+    let res = changes(&values);
+
+    assert_eq!(res.len(), 2);
+    assert_eq!(res[0].id, "org.springframework.boot");
+    assert_eq!(res[0].kind, ChangeKind::Upgraded);
+    assert_eq!(res[1].id, "com.diffplug.spotless");
+    assert_eq!(res[1].kind, ChangeKind::Added);
+  }
+}
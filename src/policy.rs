@@ -0,0 +1,280 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::config_file::read_config_file;
+use crate::glob;
+use crate::types::{ChangeKind, GradleList, ProcessedDependencyObject, SemverLevel};
+
+/// Exit code of `--check` when the policy is violated, distinct from the `--fail-on` ones.
+pub const VIOLATION_EXIT_CODE: i32 = 9;
+
+/// A set of rules evaluated against the diff, read from a TOML or YAML file:
+/// ```toml
+/// [[rule]]
+/// id = "spring-no-major"
+/// dependencies = ["org.springframework*"]
+/// deny = "major"
+/// message = "Major Spring upgrades need approval from the platform team"
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Policy {
+  #[serde(rename = "rule", alias = "rules", default)]
+  pub rules: Vec<Rule>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+  pub id: String,
+  /// What the rule denies for the matching dependencies and configurations.
+  pub deny: Deny,
+  /// `group:artifact` globs the rule applies to; all dependencies when empty.
+  #[serde(default)]
+  pub dependencies: Vec<String>,
+  /// Configuration globs the rule applies to; all configurations when empty.
+  #[serde(default)]
+  pub configurations: Vec<String>,
+  /// Configuration globs the rule never applies to, e.g. the only ones allowed to contain a dependency.
+  #[serde(default)]
+  pub except_configurations: Vec<String>,
+  /// Replaces the default violation message.
+  pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Deny {
+  /// The dependency was added to the configuration.
+  Added,
+  /// The dependency was removed from the configuration.
+  Removed,
+  /// The dependency was upgraded.
+  Upgrade,
+  /// The dependency was downgraded.
+  Downgrade,
+  /// The dependency changed major version.
+  Major,
+  /// The dependency was added, removed or changed version.
+  Change,
+  /// The dependency is present in the configuration after.
+  Present,
+  /// The dependency resolves to a `-SNAPSHOT` version after.
+  Snapshot,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Violation {
+  pub rule_id: String,
+  pub coordinate: String,
+  pub configuration: String,
+  pub version_before: String,
+  pub version_after: String,
+  pub message: String,
+}
+
+impl Policy {
+  /// Reads a policy file, see `read_config_file`.
+  pub fn from_file<P>(path: P) -> std::io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    read_config_file(path)
+  }
+
+  /// Evaluates every rule against every (dependency, configuration) pair of the diff,
+  /// except acknowledged changes.
+  pub fn check(&self, values: &[ProcessedDependencyObject]) -> Vec<Violation> {
+    let mut violations: Vec<Violation> = Vec::new();
+
+    for rule in self.rules.iter() {
+      for value in values.iter() {
+        let coordinate = format!("{}:{}", value.dependency.namespace, value.dependency.name);
+
+        if !rule.dependencies.is_empty() && !glob::matches_any(&rule.dependencies, &coordinate) {
+          continue;
+        }
+
+        for list in value
+          .gradle_versions
+          .iter()
+          .filter(|l| !l.is_acknowledged())
+          .filter(|l| rule.applies_to_configuration(&l.gradle_config_name) && rule.denies(l))
+        {
+          violations.push(Violation {
+            rule_id: rule.id.clone(),
+            coordinate: coordinate.clone(),
+            configuration: list.gradle_config_name.clone(),
+            version_before: list.version_before.clone(),
+            version_after: list.version_after.clone(),
+            message: match &rule.message {
+              Some(message) => message.clone(),
+              None => default_message(rule.deny, &coordinate, list),
+            },
+          });
+        }
+      }
+    }
+
+    violations
+  }
+}
+
+impl Rule {
+  fn applies_to_configuration(&self, configuration: &str) -> bool {
+    (self.configurations.is_empty() || glob::matches_any(&self.configurations, configuration))
+      && !glob::matches_any(&self.except_configurations, configuration)
+  }
+
+  fn denies(&self, list: &GradleList) -> bool {
+    let kind = list.change_kind();
+
+    match self.deny {
+      Deny::Added => kind == ChangeKind::Added,
+      Deny::Removed => kind == ChangeKind::Removed,
+      Deny::Upgrade => kind == ChangeKind::Upgraded,
+      Deny::Downgrade => kind == ChangeKind::Downgraded,
+      Deny::Major => SemverLevel::of(&list.version_before, &list.version_after) == SemverLevel::Major,
+      Deny::Change => kind.is_change(),
+      Deny::Present => list.version_after != "N/A",
+      Deny::Snapshot => list.version_after.ends_with("-SNAPSHOT"),
+    }
+  }
+}
+
+fn default_message(deny: Deny, coordinate: &str, list: &GradleList) -> String {
+  let (before, after, config) = (&list.version_before, &list.version_after, &list.gradle_config_name);
+
+  match deny {
+    Deny::Added => format!("{} {} was added to {}", coordinate, after, config),
+    Deny::Removed => format!("{} {} was removed from {}", coordinate, before, config),
+    Deny::Upgrade => format!("{} was upgraded from {} to {} in {}", coordinate, before, after, config),
+    Deny::Downgrade => format!(
+      "{} was downgraded from {} to {} in {}",
+      coordinate, before, after, config
+    ),
+    Deny::Major => format!(
+      "{} changed major version from {} to {} in {}",
+      coordinate, before, after, config
+    ),
+    Deny::Change => format!("{} changed from {} to {} in {}", coordinate, before, after, config),
+    Deny::Present => format!("{} is not allowed in {}", coordinate, config),
+    Deny::Snapshot => format!("{} resolves to snapshot version {} in {}", coordinate, after, config),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::dep;
+
+  const POLICY: &str = r#"
+[[rule]]
+id = "spring-no-major"
+dependencies = ["org.springframework*"]
+deny = "major"
+
+[[rule]]
+id = "no-com-sun"
+dependencies = ["com.sun*"]
+deny = "added"
+
+[[rule]]
+id = "junit-in-tests-only"
+dependencies = ["junit:junit", "org.junit*"]
+except_configurations = ["test*"]
+deny = "present"
+message = "JUnit must only be used in tests"
+
+[[rule]]
+id = "no-prod-snapshots"
+configurations = ["runtimeClasspath", "productionRuntimeClasspath"]
+deny = "snapshot"
+"#;
+
+  #[test]
+  fn it_reports_violations_with_rule_ids() {
+    let policy: Policy = toml::from_str(POLICY).unwrap();
+
+    let values = vec![
+      dep(
+        "org.springframework:spring-core",
+        vec![("runtimeClasspath", "5.3.30", "6.0.12")],
+      ),
+      dep(
+        "org.springframework.boot:spring-boot",
+        vec![("runtimeClasspath", "3.1.3", "3.1.4")],
+      ),
+      dep("com.sun.xml.bind:jaxb-impl", vec![("runtimeClasspath", "N/A", "4.0.3")]),
+      dep(
+        "junit:junit",
+        vec![
+          ("testRuntimeClasspath", "4.13.2", "4.13.2"),
+          ("runtimeClasspath", "N/A", "4.13.2"),
+        ],
+      ),
+      dep("com.example:lib", vec![("runtimeClasspath", "1.0.0", "1.1.0-SNAPSHOT")]),
+      dep(
+        "com.example:test-lib",
+        vec![("testRuntimeClasspath", "1.0.0", "1.1.0-SNAPSHOT")],
+      ),
+    ];
+
+    let violations = policy.check(&values);
+    let ids: Vec<&str> = violations.iter().map(|v| v.rule_id.as_str()).collect();
+
+    assert_eq!(
+      ids,
+      vec![
+        "spring-no-major",
+        "no-com-sun",
+        "junit-in-tests-only",
+        "no-prod-snapshots"
+      ]
+    );
+    assert_eq!(violations[2].configuration, "runtimeClasspath");
+    assert_eq!(violations[2].message, "JUnit must only be used in tests");
+    assert_eq!(
+      violations[3].message,
+      "com.example:lib resolves to snapshot version 1.1.0-SNAPSHOT in runtimeClasspath"
+    );
+  }
+
+  #[test]
+  fn it_reads_yaml_policies() {
+    let policy: Policy = serde_yaml::from_str(
+      r#"
+rules:
+  - id: no-downgrades
+    deny: downgrade
+"#,
+    )
+    .unwrap();
+
+    let values = vec![dep("com.example:lib", vec![("runtimeClasspath", "1.1.0", "1.0.0")])];
+
+    assert_eq!(policy.check(&values).len(), 1);
+  }
+
+  #[test]
+  fn it_rejects_misspelled_fields() {
+    let error = toml::from_str::<Policy>(
+      r#"
+[[rule]]
+id = "junit-in-tests-only"
+dependencies = ["junit:junit"]
+except_configuration = ["test*"]
+deny = "present"
+"#,
+    )
+    .unwrap_err();
+
+    assert!(
+      error
+        .to_string()
+        .contains("unknown field `except_configuration`"),
+      "{}",
+      error
+    );
+  }
+}
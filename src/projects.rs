@@ -0,0 +1,136 @@
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::types::{TempList, NOT_APPLICABLE};
+
+/// Exit code of `--project-alignment --fail-on-divergence` when a dependency diverges across projects.
+pub const DIVERGENCE_EXIT_CODE: i32 = 12;
+
+/// Path of the root project.
+pub const ROOT_PROJECT: &str = ":";
+
+/// Splits a configuration name into the path of its project and its unqualified name,
+/// e.g. `:service-a:runtimeClasspath` into `:service-a` and `runtimeClasspath`. \
+/// Unqualified names are the ones of the root project.
+pub fn split_configuration(name: &str) -> (&str, &str) {
+  match name
+    .strip_prefix(':')
+    .and_then(|_| name.rsplit_once(':'))
+  {
+    Some((project, configuration)) => (project, configuration),
+    None => (ROOT_PROJECT, name),
+  }
+}
+
+/// Resolved version of each dependency in one configuration of each project.
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectMatrix {
+  pub gradle_config_name: String,
+  /// Columns of the matrix, sorted by path.
+  pub projects: Vec<String>,
+  /// One row per dependency, sorted by coordinate.
+  pub rows: Vec<ProjectRow>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProjectRow {
+  pub coordinate: String,
+  /// Version in each entry of `projects`, `None` when the project doesn't have the dependency.
+  pub versions: Vec<Option<String>>,
+  /// Whether the dependency resolves to more than one version across projects.
+  pub divergent: bool,
+}
+
+impl ProjectMatrix {
+  pub fn divergent_rows(&self) -> impl Iterator<Item = &ProjectRow> {
+    self.rows.iter().filter(|r| r.divergent)
+  }
+}
+
+/// Builds the coordinate × project matrix of the configuration named `gradle_config_name` in every project.
+pub fn matrix(resolved: &[(String, Vec<TempList>)], gradle_config_name: &str) -> ProjectMatrix {
+  let mut projects: BTreeSet<&str> = BTreeSet::new();
+  let mut versions: BTreeMap<&str, BTreeMap<&str, &str>> = BTreeMap::new();
+
+  for (coordinate, temps) in resolved.iter() {
+    for temp in temps.iter().filter(|t| t.version != NOT_APPLICABLE) {
+      let (project, configuration) = split_configuration(&temp.gradle_config_name);
+      if configuration != gradle_config_name {
+        continue;
+      }
+
+      projects.insert(project);
+      versions
+        .entry(coordinate)
+        .or_default()
+        .insert(project, &temp.version);
+    }
+  }
+
+  let rows = versions
+    .into_iter()
+    .map(|(coordinate, by_project)| ProjectRow {
+      coordinate: coordinate.to_string(),
+      versions: projects
+        .iter()
+        .map(|p| by_project.get(p).map(|v| v.to_string()))
+        .collect(),
+      divergent: by_project.values().collect::<BTreeSet<_>>().len() > 1,
+    })
+    .collect();
+
+  ProjectMatrix {
+    gradle_config_name: gradle_config_name.to_string(),
+    projects: projects.into_iter().map(|p| p.to_string()).collect(),
+    rows,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::temp;
+
+  #[test]
+  fn it_splits_qualified_configuration_names() {
+    assert_eq!(split_configuration("runtimeClasspath"), (":", "runtimeClasspath"));
+    assert_eq!(
+      split_configuration(":service-a:runtimeClasspath"),
+      (":service-a", "runtimeClasspath")
+    );
+    assert_eq!(
+      split_configuration(":libs:core:compileClasspath"),
+      (":libs:core", "compileClasspath")
+    );
+  }
+
+  #[test]
+  fn it_builds_the_coordinate_by_project_matrix() {
+    let resolved = vec![
+      (
+        "io.netty:netty-handler".to_string(),
+        vec![
+          temp(":service-a:runtimeClasspath", "4.1.100.Final"),
+          temp(":service-b:runtimeClasspath", "4.1.94.Final"),
+          temp(":service-b:compileClasspath", "4.1.100.Final"),
+        ],
+      ),
+      (
+        "org.yaml:snakeyaml".to_string(),
+        vec![temp(":service-a:runtimeClasspath", "2.0")],
+      ),
+    ];
+
+    let matrix = matrix(&resolved, "runtimeClasspath");
+
+    assert_eq!(matrix.projects, vec![":service-a", ":service-b"]);
+    assert_eq!(
+      matrix.rows[0].versions,
+      vec![Some("4.1.100.Final".to_string()), Some("4.1.94.Final".to_string())]
+    );
+    assert!(matrix.rows[0].divergent);
+    assert_eq!(matrix.rows[1].versions, vec![Some("2.0".to_string()), None]);
+    assert!(!matrix.rows[1].divergent);
+    assert_eq!(matrix.divergent_rows().count(), 1);
+  }
+}
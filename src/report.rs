@@ -0,0 +1,309 @@
+use serde::Serialize;
+use std::collections::BTreeSet;
+
+use crate::alignment::Misalignment;
+use crate::catalog::{CatalogAnnotation, CatalogChange, CatalogReport};
+use crate::configurations::ConfigurationEvent;
+use crate::ordering::{ChangeGroup, GroupBy};
+use crate::plugins::{self, PluginChange};
+use crate::summary::{self, DiffSummary};
+use crate::tree_diff::ConfigurationTreeDiff;
+use crate::types::{Acknowledged, ChangeKind, ProcessedDependencyObject};
+
+/// Data passed to the HTML report template.
+#[derive(Debug, Serialize)]
+pub struct HtmlReport {
+  pub summary: DiffSummary,
+  /// Columns of the matrix, sorted by name.
+  pub configurations: Vec<String>,
+  /// One row per dependency, with one cell per entry of `configurations`.
+  pub rows: Vec<MatrixRow>,
+  pub added: Vec<ReportEntry>,
+  pub removed: Vec<ReportEntry>,
+  /// Upgrades, downgrades and other version changes.
+  pub changed: Vec<ReportEntry>,
+  /// Configurations added, removed or renamed as a whole.
+  pub configuration_events: Vec<ConfigurationEvent>,
+  /// Changes of the plugins of the buildscript classpath.
+  pub plugin_changes: Vec<PluginChange>,
+  /// Artifact families misaligned after but not before.
+  pub newly_misaligned: Vec<Misalignment>,
+  /// Version catalog entries changed between the two revisions.
+  pub catalog_changes: Vec<CatalogChange>,
+  /// Dependencies resolved after to another version than the one declared in the catalog.
+  pub catalog_mismatches: Vec<CatalogAnnotation>,
+  /// Merged before/after dependency tree of each configuration.
+  pub trees: Vec<ConfigurationTreeDiff>,
+  /// Key of `groups`, with `--group-by`.
+  pub group_by: Option<GroupBy>,
+  /// Changes grouped by `group_by`, empty without `--group-by`.
+  pub groups: Vec<ReportGroup>,
+  /// `configurations` and `rows` as JSON, embedded in the page for client-side
+  /// filtering and sorting. Safe to place inside a `<script>` element.
+  pub data: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatrixRow {
+  pub coordinate: String,
+  pub group: String,
+  pub artifact: String,
+  pub changed: bool,
+  pub cells: Vec<MatrixCell>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatrixCell {
+  /// Whether the dependency appears in this configuration on either side.
+  pub present: bool,
+  pub kind: ChangeKind,
+  pub version_before: String,
+  pub version_after: String,
+  pub acknowledged: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportGroup {
+  pub key: String,
+  pub entries: Vec<ReportEntry>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportEntry {
+  pub coordinate: String,
+  pub group: String,
+  pub artifact: String,
+  pub configuration: String,
+  pub kind: ChangeKind,
+  pub version_before: String,
+  pub version_after: String,
+  pub acknowledged: Option<Acknowledged>,
+}
+
+/// Sections of the report that aren't derived from the changes themselves, all empty by default.
+#[derive(Debug, Default)]
+pub struct ReportInputs {
+  pub trees: Vec<ConfigurationTreeDiff>,
+  pub configuration_events: Vec<ConfigurationEvent>,
+  pub newly_misaligned: Vec<Misalignment>,
+  pub catalog: CatalogReport,
+  pub group_by: Option<GroupBy>,
+  pub groups: Vec<ChangeGroup>,
+}
+
+/// Builds the configuration × dependency matrix and the added/removed/changed
+/// sections rendered by `report_template.hbs`, in the order of `values`.
+pub fn build(values: &[ProcessedDependencyObject], inputs: ReportInputs) -> HtmlReport {
+  let ReportInputs {
+    trees,
+    configuration_events,
+    newly_misaligned,
+    catalog,
+    group_by,
+    groups,
+  } = inputs;
+
+  let configurations: Vec<String> = values
+    .iter()
+    .flat_map(|v| {
+      v.gradle_versions
+        .iter()
+        .map(|l| l.gradle_config_name.clone())
+    })
+    .collect::<BTreeSet<String>>()
+    .into_iter()
+    .collect();
+
+  let mut report = HtmlReport {
+    summary: summary::summarize(values),
+    plugin_changes: plugins::changes(values),
+    configurations: Vec::new(),
+    rows: Vec::new(),
+    added: Vec::new(),
+    removed: Vec::new(),
+    changed: Vec::new(),
+    configuration_events,
+    newly_misaligned,
+    catalog_mismatches: catalog.mismatches().cloned().collect(),
+    catalog_changes: catalog.changes,
+    trees,
+    group_by,
+    groups: groups.into_iter().map(report_group).collect(),
+    data: String::new(),
+  };
+
+  for value in values.iter() {
+    let coordinate = format!("{}:{}", value.dependency.namespace, value.dependency.name);
+
+    let cells = configurations
+      .iter()
+      .map(|config| {
+        match value
+          .gradle_versions
+          .iter()
+          .find(|l| &l.gradle_config_name == config)
+        {
+          Some(list) => MatrixCell {
+            present: true,
+            kind: list.change_kind(),
+            version_before: list.version_before.clone(),
+            version_after: list.version_after.clone(),
+            acknowledged: list.is_acknowledged(),
+          },
+          None => MatrixCell {
+            present: false,
+            kind: ChangeKind::Unchanged,
+            version_before: "N/A".to_string(),
+            version_after: "N/A".to_string(),
+            acknowledged: false,
+          },
+        }
+      })
+      .collect::<Vec<MatrixCell>>();
+
+    for list in value.gradle_versions.iter() {
+      let kind = list.change_kind();
+
+      let section = match kind {
+        ChangeKind::Unchanged => continue,
+        ChangeKind::Added => &mut report.added,
+        ChangeKind::Removed => &mut report.removed,
+        ChangeKind::Upgraded | ChangeKind::Downgraded | ChangeKind::Changed => &mut report.changed,
+      };
+
+      section.push(ReportEntry {
+        coordinate: coordinate.clone(),
+        group: value.dependency.namespace.clone(),
+        artifact: value.dependency.name.clone(),
+        configuration: list.gradle_config_name.clone(),
+        kind,
+        version_before: list.version_before.clone(),
+        version_after: list.version_after.clone(),
+        acknowledged: list.acknowledged.clone(),
+      });
+    }
+
+    let changed = cells.iter().any(|c| c.kind.is_change());
+
+    report.rows.push(MatrixRow {
+      coordinate,
+      group: value.dependency.namespace.clone(),
+      artifact: value.dependency.name.clone(),
+      changed,
+      cells,
+    });
+  }
+
+  report.configurations = configurations;
+  report.data = embeddable_json(&serde_json::json!({
+    "configurations": report.configurations,
+    "rows": report.rows,
+  }));
+  report
+}
+
+fn report_group(group: ChangeGroup) -> ReportGroup {
+  ReportGroup {
+    key: group.key,
+    entries: group
+      .changes
+      .into_iter()
+      .map(|change| {
+        let (group, artifact) = change
+          .coordinate
+          .split_once(':')
+          .unwrap_or(("", &change.coordinate));
+
+        ReportEntry {
+          group: group.to_string(),
+          artifact: artifact.to_string(),
+          coordinate: change.coordinate.clone(),
+          configuration: change.configuration,
+          kind: change.kind,
+          version_before: change.version_before,
+          version_after: change.version_after,
+          acknowledged: change.acknowledged,
+        }
+      })
+      .collect(),
+  }
+}
+
+/// Serialises `value` so it can be embedded in an HTML `<script>` element:
+/// `<`, `>` and `&` are escaped so the data can never close the element early.
+fn embeddable_json(value: &serde_json::Value) -> String {
+  serde_json::to_string(value)
+    .unwrap()
+    .replace('<', "\\u003c")
+    .replace('>', "\\u003e")
+    .replace('&', "\\u0026")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::dep;
+
+  #[test]
+  fn it_builds_a_cell_for_every_configuration() {
+    let values = vec![
+      dep(
+        "dep_ns:a",
+        vec![
+          ("runtimeClasspath", "1.0.0", "1.1.0"),
+          ("compileClasspath", "1.0.0", "1.0.0"),
+        ],
+      ),
+      dep("dep_ns:b", vec![("testRuntimeClasspath", "N/A", "2.0.0")]),
+    ];
+
+    let report = build(&values, ReportInputs::default());
+
+    assert_eq!(
+      report.configurations,
+      vec!["compileClasspath", "runtimeClasspath", "testRuntimeClasspath"]
+    );
+    assert!(report.rows.iter().all(|r| r.cells.len() == 3));
+
+    /* a change in a configuration other than the first must be visible */
+    assert_eq!(report.rows[0].cells[1].kind, ChangeKind::Upgraded);
+    assert!(!report.rows[0].cells[2].present);
+    assert!(report.rows[0].changed);
+  }
+
+  #[test]
+  fn it_splits_entries_into_sections() {
+    let values = vec![
+      dep(
+        "dep_ns:a",
+        vec![
+          ("runtimeClasspath", "1.0.0", "0.9.0"),
+          ("compileClasspath", "1.0.0", "N/A"),
+        ],
+      ),
+      dep("dep_ns:b", vec![("runtimeClasspath", "N/A", "2.0.0")]),
+      dep("dep_ns:c", vec![("runtimeClasspath", "3.0.0", "3.0.0")]),
+    ];
+
+    let report = build(&values, ReportInputs::default());
+
+    assert_eq!(report.added.len(), 1);
+    assert_eq!(report.removed.len(), 1);
+    assert_eq!(report.changed.len(), 1);
+    assert_eq!(report.summary.downgraded, 1);
+    assert_eq!(report.summary.changed_dependencies, 2);
+    assert!(!report.rows[2].changed);
+  }
+
+  #[test]
+  fn it_embeds_data_that_cannot_close_the_script_element() {
+    let values = vec![dep("dep_ns:</script><b>", vec![("runtimeClasspath", "1.0.0", "1.1.0")])];
+
+    let report = build(&values, ReportInputs::default());
+
+    assert!(!report.data.contains('<'));
+    let parsed: serde_json::Value = serde_json::from_str(&report.data).unwrap();
+    assert_eq!(parsed["rows"][0]["artifact"], "</script><b>");
+    assert_eq!(parsed["configurations"][0], "runtimeClasspath");
+  }
+}
@@ -0,0 +1,252 @@
+use rustc_hash::FxHashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+use crate::types::{Dependency, GradleEntry, Version, Versions};
+
+const SNAPSHOT_FORMAT_VERSION: u32 = 1;
+
+/// A reviewed set of parsed dependencies, committed to a repository and diffed against
+/// fresh Gradle outputs. \
+/// Dependencies, configurations and versions are kept sorted, so that snapshots
+/// written from the same output are identical and diff cleanly in git:
+/// ```json
+/// {
+///   "format_version": 1,
+///   "dependencies": {
+///     "com.google.guava:guava": {
+///       "compileClasspath": { "transitive": ["31.1-jre"], "pinned": null }
+///     }
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+  pub format_version: u32,
+  /// `group:artifact` -> configuration -> versions
+  pub dependencies: BTreeMap<String, BTreeMap<String, SnapshotVersions>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SnapshotVersions {
+  pub transitive: Vec<String>,
+  pub pinned: Option<String>,
+}
+
+impl Default for Snapshot {
+  fn default() -> Self {
+    Self {
+      format_version: SNAPSHOT_FORMAT_VERSION,
+      dependencies: BTreeMap::new(),
+    }
+  }
+}
+
+impl Snapshot {
+  pub fn from_dep_map(dep_map: &FxHashMap<String, Dependency>) -> Self {
+    let mut snapshot = Snapshot::default();
+
+    for dep in dep_map.values() {
+      let configurations = dep
+        .gradle_entries
+        .iter()
+        .map(|entry| {
+          let mut transitive: Vec<String> = entry
+            .versions
+            .transitive
+            .iter()
+            .filter(|v| v.is_applicable())
+            .map(|v| v.to_string())
+            .collect();
+          transitive.sort();
+
+          let pinned = match entry.versions.pinned.is_applicable() {
+            true => Some(entry.versions.pinned.to_string()),
+            false => None,
+          };
+
+          (
+            entry.gradle_config_name.clone(),
+            SnapshotVersions { transitive, pinned },
+          )
+        })
+        .collect();
+
+      snapshot
+        .dependencies
+        .insert(format!("{}:{}", dep.namespace, dep.name), configurations);
+    }
+
+    snapshot
+  }
+
+  /// Converts the snapshot back to a dependency map, keyed by `group:artifact` like the `DependencyParser` maps.
+  pub fn to_dep_map(&self) -> FxHashMap<String, Dependency> {
+    let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+
+    for (coordinate, configurations) in self.dependencies.iter() {
+      let (namespace, name) = coordinate.split_once(':').unwrap_or(("", coordinate));
+
+      let gradle_entries = configurations
+        .iter()
+        .map(|(config, versions)| GradleEntry {
+          gradle_config_name: config.clone(),
+          versions: Versions {
+            transitive: match versions.transitive.is_empty() {
+              true => vec![Version::NotApplicable],
+              false => versions
+                .transitive
+                .iter()
+                .map(|v| Version::Transitive(v.clone()))
+                .collect(),
+            },
+            pinned: match &versions.pinned {
+              Some(v) => Version::Pinned(v.clone()),
+              None => Version::NotApplicable,
+            },
+          },
+        })
+        .collect();
+
+      dep_map.insert(
+        coordinate.clone(),
+        Dependency {
+          name: name.to_string(),
+          namespace: namespace.to_string(),
+          gradle_entries,
+        },
+      );
+    }
+
+    dep_map
+  }
+
+  pub fn from_file<P>(path: P) -> std::io::Result<Self>
+  where
+    P: AsRef<Path>,
+  {
+    let content = fs::read_to_string(path)?;
+    let snapshot: Snapshot = serde_json::from_str(&content).map_err(std::io::Error::other)?;
+
+    if snapshot.format_version > SNAPSHOT_FORMAT_VERSION {
+      return Err(std::io::Error::other(format!(
+        "unsupported format_version {} (supported up to {})",
+        snapshot.format_version, SNAPSHOT_FORMAT_VERSION
+      )));
+    }
+
+    Ok(snapshot)
+  }
+
+  pub fn write_to_file<P>(&self, path: P) -> std::io::Result<()>
+  where
+    P: AsRef<Path>,
+  {
+    let mut content = serde_json::to_string_pretty(self).map_err(std::io::Error::other)?;
+    content.push('\n');
+    fs::write(path, content)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn dep(name: &str, entries: Vec<(&str, Vec<&str>, Option<&str>)>) -> Dependency {
+    Dependency {
+      name: name.to_string(),
+      namespace: "dep_ns".to_string(),
+      gradle_entries: entries
+        .into_iter()
+        .map(|(config, transitive, pinned)| GradleEntry {
+          gradle_config_name: config.to_string(),
+          versions: Versions {
+            transitive: transitive
+              .into_iter()
+              .map(|v| match v {
+                "N/A" => Version::NotApplicable,
+                _ => Version::Transitive(v.to_string()),
+              })
+              .collect(),
+            pinned: match pinned {
+              Some(v) => Version::Pinned(v.to_string()),
+              None => Version::NotApplicable,
+            },
+          },
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn it_writes_sorted_snapshots() {
+    let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+    dep_map.insert(
+      "b".to_string(),
+      dep(
+        "b",
+        vec![
+          ("runtimeClasspath", vec!["1.2.0", "1.10.0"], None),
+          ("compileClasspath", vec!["N/A"], Some("2.0.0")),
+        ],
+      ),
+    );
+    dep_map.insert(
+      "a".to_string(),
+      dep("a", vec![("runtimeClasspath", vec!["1.0.0"], None)]),
+    );
+
+    let json = serde_json::to_string(&Snapshot::from_dep_map(&dep_map)).unwrap();
+
+    assert_eq!(
+      json,
+      r#"{"format_version":1,"dependencies":{"dep_ns:a":{"runtimeClasspath":{"transitive":["1.0.0"],"pinned":null}},"dep_ns:b":{"compileClasspath":{"transitive":[],"pinned":"2.0.0"},"runtimeClasspath":{"transitive":["1.10.0","1.2.0"],"pinned":null}}}}"#
+    );
+  }
+
+  #[test]
+  fn it_round_trips_through_dep_maps() {
+    let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+    dep_map.insert(
+      "b".to_string(),
+      dep(
+        "b",
+        vec![
+          ("runtimeClasspath", vec!["1.2.0"], Some("1.3.0")),
+          ("compileClasspath", vec!["N/A"], Some("2.0.0")),
+        ],
+      ),
+    );
+
+    let snapshot = Snapshot::from_dep_map(&dep_map);
+    let restored = snapshot.to_dep_map();
+
+    let entries = &restored.get("dep_ns:b").unwrap().gradle_entries;
+    assert_eq!(entries[0].gradle_config_name, "compileClasspath");
+    assert!(!entries[0].versions.transitive[0].is_applicable());
+    assert!(matches!(entries[1].versions.pinned, Version::Pinned(_)));
+    assert_eq!(Snapshot::from_dep_map(&restored), snapshot);
+  }
+
+  #[test]
+  fn it_keeps_artifacts_of_the_same_name_in_different_groups() {
+    let mut other = dep("annotations", vec![("compileClasspath", vec!["24.0.1"], None)]);
+    other.namespace = "org.jetbrains".to_string();
+
+    let mut dep_map: FxHashMap<String, Dependency> = FxHashMap::default();
+    dep_map.insert(
+      "dep_ns:annotations".to_string(),
+      dep("annotations", vec![("compileClasspath", vec!["1.0.0"], None)]),
+    );
+    dep_map.insert("org.jetbrains:annotations".to_string(), other);
+
+    let snapshot = Snapshot::from_dep_map(&dep_map);
+    let restored = snapshot.to_dep_map();
+
+    assert_eq!(restored.len(), 2);
+    assert_eq!(restored["org.jetbrains:annotations"].namespace, "org.jetbrains");
+    assert_eq!(Snapshot::from_dep_map(&restored), snapshot);
+  }
+}
@@ -0,0 +1,242 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::types::{numeric_parts, ChangeKind, ProcessedDependencyObject, SemverLevel};
+
+/// Number of entries of `DiffSummary::largest_jumps`.
+const LARGEST_JUMPS: usize = 5;
+
+/// Statistics of a diff, shown first in every output. \
+/// Change counts are of (dependency, configuration) pairs.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct DiffSummary {
+  /// Dependencies present on either side.
+  pub dependencies: usize,
+  pub dependencies_before: usize,
+  pub dependencies_after: usize,
+  pub changed_dependencies: usize,
+  pub configurations: usize,
+  /// Configurations with dependencies only after.
+  pub configurations_added: usize,
+  /// Configurations with dependencies only before.
+  pub configurations_removed: usize,
+  pub added: usize,
+  pub removed: usize,
+  pub upgraded: usize,
+  pub downgraded: usize,
+  pub other_changes: usize,
+  pub acknowledged: usize,
+  /// Statistics of each configuration, sorted by name.
+  pub per_configuration: Vec<ConfigurationSummary>,
+  /// Version changes spanning the most significant components, largest first.
+  pub largest_jumps: Vec<VersionJump>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ConfigurationSummary {
+  pub gradle_config_name: String,
+  pub dependencies_before: usize,
+  pub dependencies_after: usize,
+  pub added: usize,
+  pub removed: usize,
+  pub upgraded: usize,
+  pub downgraded: usize,
+  pub other_changes: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct VersionJump {
+  pub coordinate: String,
+  pub version_before: String,
+  pub version_after: String,
+  pub level: SemverLevel,
+  /// Configurations the change happened in.
+  pub configurations: Vec<String>,
+}
+
+impl ConfigurationSummary {
+  fn is_changed(&self) -> bool {
+    self.added + self.removed + self.upgraded + self.downgraded + self.other_changes > 0
+  }
+}
+
+pub fn summarize(values: &[ProcessedDependencyObject]) -> DiffSummary {
+  let mut summary = DiffSummary {
+    dependencies: values.len(),
+    ..Default::default()
+  };
+  let mut configurations: BTreeMap<&str, ConfigurationSummary> = BTreeMap::new();
+  let mut jumps: Vec<VersionJump> = Vec::new();
+
+  for value in values.iter() {
+    let coordinate = format!("{}:{}", value.dependency.namespace, value.dependency.name);
+    let mut before = false;
+    let mut after = false;
+    let mut changed = false;
+
+    for list in value.gradle_versions.iter() {
+      let config = configurations
+        .entry(&list.gradle_config_name)
+        .or_insert_with(|| ConfigurationSummary {
+          gradle_config_name: list.gradle_config_name.clone(),
+          ..Default::default()
+        });
+
+      let kind = list.change_kind();
+      if kind != ChangeKind::Added {
+        before = true;
+        config.dependencies_before += 1;
+      }
+      if kind != ChangeKind::Removed {
+        after = true;
+        config.dependencies_after += 1;
+      }
+
+      let (total, per_config) = match kind {
+        ChangeKind::Unchanged => continue,
+        ChangeKind::Added => (&mut summary.added, &mut config.added),
+        ChangeKind::Removed => (&mut summary.removed, &mut config.removed),
+        ChangeKind::Upgraded => (&mut summary.upgraded, &mut config.upgraded),
+        ChangeKind::Downgraded => (&mut summary.downgraded, &mut config.downgraded),
+        ChangeKind::Changed => (&mut summary.other_changes, &mut config.other_changes),
+      };
+
+      *total += 1;
+      *per_config += 1;
+      changed = true;
+      if list.is_acknowledged() {
+        summary.acknowledged += 1;
+      }
+
+      let level = SemverLevel::of(&list.version_before, &list.version_after);
+      if level == SemverLevel::None {
+        continue;
+      }
+
+      match jumps.iter_mut().find(|j| {
+        j.coordinate == coordinate && j.version_before == list.version_before && j.version_after == list.version_after
+      }) {
+        Some(jump) => jump
+          .configurations
+          .push(list.gradle_config_name.clone()),
+        None => jumps.push(VersionJump {
+          coordinate: coordinate.clone(),
+          version_before: list.version_before.clone(),
+          version_after: list.version_after.clone(),
+          level,
+          configurations: vec![list.gradle_config_name.clone()],
+        }),
+      }
+    }
+
+    summary.dependencies_before += usize::from(before);
+    summary.dependencies_after += usize::from(after);
+    summary.changed_dependencies += usize::from(changed);
+  }
+
+  /* the most significant level first, then the largest difference of that component */
+  jumps.sort_by(|a, b| {
+    b.level
+      .cmp(&a.level)
+      .then_with(|| distance(b).cmp(&distance(a)))
+      .then_with(|| a.coordinate.cmp(&b.coordinate))
+  });
+  jumps.truncate(LARGEST_JUMPS);
+
+  summary.configurations = configurations.len();
+  summary.configurations_added = configurations
+    .values()
+    .filter(|c| c.dependencies_before == 0)
+    .count();
+  summary.configurations_removed = configurations
+    .values()
+    .filter(|c| c.dependencies_after == 0)
+    .count();
+  summary.per_configuration = configurations.into_values().collect();
+  summary.largest_jumps = jumps;
+  summary
+}
+
+impl DiffSummary {
+  /// Per-configuration statistics of the configurations with changes.
+  pub fn changed_configurations(&self) -> impl Iterator<Item = &ConfigurationSummary> {
+    self
+      .per_configuration
+      .iter()
+      .filter(|c| c.is_changed())
+  }
+}
+
+/// Difference of the most significant differing numeric component of a jump.
+fn distance(jump: &VersionJump) -> u64 {
+  let before = numeric_parts(&jump.version_before);
+  let after = numeric_parts(&jump.version_after);
+
+  before
+    .iter()
+    .zip(after.iter())
+    .find(|(b, a)| b != a)
+    .map_or(0, |(b, a)| b.abs_diff(*a))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::dep;
+
+  #[test]
+  fn it_counts_dependencies_and_changes_per_configuration() {
+    let values = vec![
+      dep(
+        "dep_ns:a",
+        vec![
+          ("runtimeClasspath", "1.0.0", "1.1.0"),
+          ("compileClasspath", "1.0.0", "1.0.0"),
+        ],
+      ),
+      dep("dep_ns:b", vec![("runtimeClasspath", "2.0.0", "N/A")]),
+      dep("dep_ns:c", vec![("testFixturesRuntimeClasspath", "N/A", "1.0.0")]),
+    ];
+
+    let summary = summarize(&values);
+
+    assert_eq!(summary.dependencies, 3);
+    assert_eq!(summary.dependencies_before, 2);
+    assert_eq!(summary.dependencies_after, 2);
+    assert_eq!(summary.changed_dependencies, 3);
+    assert_eq!((summary.added, summary.removed, summary.upgraded), (1, 1, 1));
+    assert_eq!(summary.configurations_added, 1);
+    assert_eq!(summary.configurations_removed, 0);
+
+    let runtime = &summary.per_configuration[1];
+    assert_eq!(runtime.gradle_config_name, "runtimeClasspath");
+    assert_eq!((runtime.dependencies_before, runtime.dependencies_after), (2, 1));
+    assert_eq!(summary.changed_configurations().count(), 2);
+  }
+
+  #[test]
+  fn it_ranks_the_largest_jumps() {
+    let values = vec![
+      dep("dep_ns:minor", vec![("runtimeClasspath", "1.0.0", "1.5.0")]),
+      dep(
+        "dep_ns:major",
+        vec![
+          ("runtimeClasspath", "1.0.0", "2.0.0"),
+          ("compileClasspath", "1.0.0", "2.0.0"),
+        ],
+      ),
+      dep("dep_ns:bigger-major", vec![("runtimeClasspath", "1.0.0", "4.0.0")]),
+    ];
+
+    let summary = summarize(&values);
+
+    let coordinates: Vec<&str> = summary
+      .largest_jumps
+      .iter()
+      .map(|j| j.coordinate.as_str())
+      .collect();
+    assert_eq!(coordinates, vec!["dep_ns:bigger-major", "dep_ns:major", "dep_ns:minor"]);
+    assert_eq!(summary.largest_jumps[1].configurations.len(), 2);
+  }
+}
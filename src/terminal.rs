@@ -0,0 +1,654 @@
+use clap::ValueEnum;
+use std::io::IsTerminal;
+
+use crate::alignment::Misalignment;
+use crate::catalog::CatalogReport;
+use crate::configurations::ConfigurationEvent;
+use crate::consistency::Inconsistency;
+use crate::insight::SelectionChange;
+use crate::ordering::{ChangeGroup, GroupBy, GroupedChange};
+use crate::plugins::PluginChange;
+use crate::policy::Violation;
+use crate::projects::ProjectMatrix;
+use crate::summary::DiffSummary;
+use crate::types::{ChangeKind, NOT_APPLICABLE};
+
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RESET: &str = "\x1b[0m";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorChoice {
+  Auto,
+  Always,
+  Never,
+}
+
+impl ColorChoice {
+  /// Resolves whether to colourise output. `auto` colours only when stdout is a
+  /// terminal and `NO_COLOR` is unset or empty (see https://no-color.org).
+  pub fn enabled(&self) -> bool {
+    match self {
+      ColorChoice::Always => true,
+      ColorChoice::Never => false,
+      ColorChoice::Auto => {
+        let no_color = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+        !no_color && std::io::stdout().is_terminal()
+      }
+    }
+  }
+}
+
+/// Renders the changed dependencies grouped by `group_by` (see `ordering::group`), `git diff` style:
+/// * `+` (green) for dependencies added to a configuration
+/// * `-` (red) for dependencies removed from a configuration
+/// * `~` (yellow) for dependencies whose version changed
+///
+/// Unless grouped by configuration, each line ends with its `[configuration]`.
+/// Acknowledged changes are dimmed.
+pub fn render(groups: &[ChangeGroup], group_by: GroupBy, color: bool) -> String {
+  if groups.is_empty() {
+    return "No dependency changes.\n".to_string();
+  }
+
+  let mut out = String::new();
+
+  for group in groups.iter() {
+    out.push_str(&paint(&group.key, BOLD, color));
+    out.push('\n');
+
+    for change in group.changes.iter() {
+      out.push_str("  ");
+      out.push_str(&render_line(change, group_by != GroupBy::Configuration, color));
+      out.push('\n');
+    }
+
+    out.push('\n');
+  }
+
+  out
+}
+
+/// Renders the summary printed above the changes: totals, the changed configurations
+/// and the largest version jumps.
+pub fn render_summary(summary: &DiffSummary, color: bool) -> String {
+  let mut out = String::new();
+
+  out.push_str(&paint(
+    &format!(
+      "{} of {} dependencies changed ({} before, {} after) in {} configurations",
+      summary.changed_dependencies,
+      summary.dependencies,
+      summary.dependencies_before,
+      summary.dependencies_after,
+      summary.configurations
+    ),
+    BOLD,
+    color,
+  ));
+  out.push('\n');
+
+  out.push_str(&format!(
+    "  {}  {}  {}  {}  ~{} other",
+    paint(&format!("+{} added", summary.added), GREEN, color),
+    paint(&format!("-{} removed", summary.removed), RED, color),
+    paint(&format!("~{} upgraded", summary.upgraded), YELLOW, color),
+    paint(&format!("~{} downgraded", summary.downgraded), YELLOW, color),
+    summary.other_changes
+  ));
+  if summary.acknowledged > 0 {
+    out.push_str(&format!(" ({} acknowledged)", summary.acknowledged));
+  }
+  out.push('\n');
+
+  if summary.configurations_added + summary.configurations_removed > 0 {
+    out.push_str(&format!(
+      "  configurations: {} added, {} removed\n",
+      summary.configurations_added, summary.configurations_removed
+    ));
+  }
+
+  for config in summary.changed_configurations() {
+    out.push_str(&format!(
+      "  {}: {} -> {} dependencies (+{} -{} ~{})\n",
+      config.gradle_config_name,
+      config.dependencies_before,
+      config.dependencies_after,
+      config.added,
+      config.removed,
+      config.upgraded + config.downgraded + config.other_changes
+    ));
+  }
+
+  if !summary.largest_jumps.is_empty() {
+    out.push_str("Largest jumps\n");
+
+    for jump in summary.largest_jumps.iter() {
+      out.push_str(&format!(
+        "  {} {} -> {} ({})\n",
+        jump.coordinate, jump.version_before, jump.version_after, jump.level
+      ));
+    }
+  }
+
+  out.push('\n');
+  out
+}
+
+/// Renders the configurations added (`+`), removed (`-`) or renamed (`~`) as a whole,
+/// or nothing when there are none.
+pub fn render_configuration_events(events: &[ConfigurationEvent], color: bool) -> String {
+  if events.is_empty() {
+    return String::new();
+  }
+
+  let mut out = paint("Configurations", BOLD, color);
+  out.push('\n');
+
+  for event in events.iter() {
+    let line = match event {
+      ConfigurationEvent::Added {
+        gradle_config_name,
+        dependencies,
+      } => paint(
+        &format!("+ {} ({} dependencies)", gradle_config_name, dependencies),
+        GREEN,
+        color,
+      ),
+      ConfigurationEvent::Removed {
+        gradle_config_name,
+        dependencies,
+      } => paint(
+        &format!("- {} ({} dependencies)", gradle_config_name, dependencies),
+        RED,
+        color,
+      ),
+      ConfigurationEvent::Renamed { from, to, similarity } => paint(
+        &format!("~ {} -> {} (renamed, {:.0}% similar)", from, to, similarity * 100.0),
+        YELLOW,
+        color,
+      ),
+    };
+
+    out.push_str("  ");
+    out.push_str(&line);
+    out.push('\n');
+  }
+
+  out.push('\n');
+  out
+}
+
+/// Renders the plugins added, removed or changing version, nothing when there are none.
+pub fn render_plugin_changes(changes: &[PluginChange], color: bool) -> String {
+  if changes.is_empty() {
+    return String::new();
+  }
+
+  let mut out = paint("Plugins", BOLD, color);
+  out.push('\n');
+
+  for change in changes.iter() {
+    let line = match change.kind {
+      ChangeKind::Added => paint(&format!("+ {} {}", change.id, change.version_after), GREEN, color),
+      ChangeKind::Removed => paint(&format!("- {} {}", change.id, change.version_before), RED, color),
+      _ => paint(
+        &format!("~ {} {} -> {}", change.id, change.version_before, change.version_after),
+        YELLOW,
+        color,
+      ),
+    };
+
+    out.push_str("  ");
+    out.push_str(&line);
+    out.push_str(&format!(" [{}]\n", change.gradle_config_name));
+  }
+
+  out.push('\n');
+  out
+}
+
+/// Renders the catalog changes and the dependencies resolved to another version than their catalog one,
+/// nothing when there are none.
+pub fn render_catalog(catalog: &CatalogReport, color: bool) -> String {
+  let mut out = String::new();
+
+  if !catalog.changes.is_empty() {
+    out.push_str(&paint("Version catalog", BOLD, color));
+    out.push('\n');
+
+    for change in catalog.changes.iter() {
+      let entry = format!("{}.{}", change.section, change.alias);
+      let line = match change.kind {
+        ChangeKind::Added => paint(&format!("+ {} {}", entry, change.value_after), GREEN, color),
+        ChangeKind::Removed => paint(&format!("- {} {}", entry, change.value_before), RED, color),
+        _ => paint(
+          &format!("~ {} {} -> {}", entry, change.value_before, change.value_after),
+          YELLOW,
+          color,
+        ),
+      };
+
+      out.push_str("  ");
+      out.push_str(&line);
+      out.push('\n');
+    }
+    out.push('\n');
+  }
+
+  if catalog.mismatches().next().is_some() {
+    out.push_str(&paint("Catalog mismatches", BOLD, color));
+    out.push('\n');
+
+    for mismatch in catalog.mismatches() {
+      out.push_str(&format!(
+        "  {} {}, declared {} as {}.{} [{}]\n",
+        paint(&mismatch.coordinate, YELLOW, color),
+        mismatch.resolved_version,
+        mismatch
+          .declared_version
+          .as_deref()
+          .unwrap_or(NOT_APPLICABLE),
+        mismatch.section,
+        mismatch.alias,
+        mismatch.gradle_config_names.join(", ")
+      ));
+    }
+    out.push('\n');
+  }
+
+  out
+}
+
+/// Renders the families misaligned after but not before, nothing when there are none.
+pub fn render_newly_misaligned(misalignments: &[Misalignment], color: bool) -> String {
+  if misalignments.is_empty() {
+    return String::new();
+  }
+
+  let mut out = paint("Newly misaligned families", BOLD, color);
+  out.push('\n');
+  out.push_str(&render_misalignment_members(misalignments, "  ", color));
+  out.push('\n');
+  out
+}
+
+pub fn render_misalignments(misalignments: &[Misalignment], color: bool) -> String {
+  if misalignments.is_empty() {
+    return "No misaligned families.\n".to_string();
+  }
+
+  let mut out = render_misalignment_members(misalignments, "", color);
+  out.push_str(&format!("\n{} misaligned families\n", misalignments.len()));
+  out
+}
+
+/// One `family in configuration: versions` line per misalignment, followed by one line per member.
+fn render_misalignment_members(misalignments: &[Misalignment], indent: &str, color: bool) -> String {
+  let mut out = String::new();
+
+  for misalignment in misalignments.iter() {
+    let versions: Vec<&str> = misalignment.versions().into_iter().collect();
+    out.push_str(indent);
+    out.push_str(&paint(
+      &format!(
+        "! {} in {}: {}",
+        misalignment.family,
+        misalignment.gradle_config_name,
+        versions.join(", ")
+      ),
+      YELLOW,
+      color,
+    ));
+    out.push('\n');
+
+    for member in misalignment.members.iter() {
+      out.push_str(&format!("{}    {} {}\n", indent, member.coordinate, member.version));
+    }
+  }
+
+  out
+}
+
+/// Renders the coordinate × project matrix as a table, marking and highlighting the divergent rows.
+pub fn render_project_matrix(matrix: &ProjectMatrix, color: bool) -> String {
+  if matrix.rows.is_empty() {
+    return format!("No dependencies in {} of any project.\n", matrix.gradle_config_name);
+  }
+
+  let cells: Vec<Vec<&str>> = matrix
+    .rows
+    .iter()
+    .map(|r| {
+      std::iter::once(r.coordinate.as_str())
+        .chain(r.versions.iter().map(|v| v.as_deref().unwrap_or("-")))
+        .collect()
+    })
+    .collect();
+  let header: Vec<&str> = std::iter::once(matrix.gradle_config_name.as_str())
+    .chain(matrix.projects.iter().map(|p| p.as_str()))
+    .collect();
+
+  let widths: Vec<usize> = (0..header.len())
+    .map(|i| {
+      cells
+        .iter()
+        .chain(std::iter::once(&header))
+        .map(|row| row[i].len())
+        .max()
+        .unwrap_or(0)
+    })
+    .collect();
+  let line = |row: &[&str]| {
+    row
+      .iter()
+      .zip(widths.iter())
+      .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+      .collect::<Vec<String>>()
+      .join("  ")
+      .trim_end()
+      .to_string()
+  };
+
+  let mut out = paint(&format!("  {}", line(&header)), BOLD, color);
+  out.push('\n');
+
+  for (row, cells) in matrix.rows.iter().zip(cells.iter()) {
+    let text = match row.divergent {
+      true => paint(&format!("! {}", line(cells)), RED, color),
+      false => format!("  {}", line(cells)),
+    };
+    out.push_str(&text);
+    out.push('\n');
+  }
+
+  out.push_str(&format!(
+    "\n{} of {} dependencies diverge across {} projects\n",
+    matrix.divergent_rows().count(),
+    matrix.rows.len(),
+    matrix.projects.len()
+  ));
+  out
+}
+
+/// Renders each change of selection as the module line followed by its previous and new selection reasons
+/// and its added and removed requesters.
+pub fn render_selection_changes(changes: &[SelectionChange], color: bool) -> String {
+  if changes.is_empty() {
+    return "No selection changes.\n".to_string();
+  }
+
+  let mut out = String::new();
+
+  for change in changes.iter() {
+    out.push_str(&paint(
+      &format!(
+        "{} {} -> {}",
+        change.coordinate,
+        change
+          .version_before
+          .as_deref()
+          .unwrap_or(NOT_APPLICABLE),
+        change
+          .version_after
+          .as_deref()
+          .unwrap_or(NOT_APPLICABLE)
+      ),
+      BOLD,
+      color,
+    ));
+    out.push('\n');
+
+    if change.reasons_before != change.reasons_after {
+      let reasons = |reasons: &[String]| match reasons.is_empty() {
+        true => "-".to_string(),
+        false => reasons.join(", "),
+      };
+      out.push_str(&format!(
+        "  was: {}, now: {}\n",
+        reasons(&change.reasons_before),
+        reasons(&change.reasons_after)
+      ));
+    }
+
+    for requester in change
+      .requested_by_after
+      .iter()
+      .filter(|r| !change.requested_by_before.contains(r))
+    {
+      out.push_str(&paint(&format!("  + requested by {}", requester), GREEN, color));
+      out.push('\n');
+    }
+    for requester in change
+      .requested_by_before
+      .iter()
+      .filter(|r| !change.requested_by_after.contains(r))
+    {
+      out.push_str(&paint(&format!("  - requested by {}", requester), RED, color));
+      out.push('\n');
+    }
+  }
+
+  out
+}
+
+/// Renders one `[rule-id] message` line per policy violation.
+pub fn render_violations(violations: &[Violation], color: bool) -> String {
+  if violations.is_empty() {
+    return "No policy violations.\n".to_string();
+  }
+
+  let mut out = String::new();
+
+  for violation in violations.iter() {
+    out.push_str(&paint(&format!("[{}]", violation.rule_id), RED, color));
+    out.push(' ');
+    out.push_str(&violation.message);
+    out.push('\n');
+  }
+
+  out.push_str(&format!("\n{} policy violation(s)\n", violations.len()));
+  out
+}
+
+pub fn render_inconsistencies(inconsistencies: &[Inconsistency], color: bool) -> String {
+  if inconsistencies.is_empty() {
+    return "No inconsistent versions.\n".to_string();
+  }
+
+  let mut out = String::new();
+
+  for inconsistency in inconsistencies.iter() {
+    out.push_str(&paint(&inconsistency.coordinate, YELLOW, color));
+    out.push_str(&format!(
+      " {} in {}, {} in {}\n",
+      inconsistency.version,
+      inconsistency.configuration,
+      inconsistency.other_version,
+      inconsistency.other_configuration
+    ));
+  }
+
+  out.push_str(&format!("\n{} inconsistent version(s)\n", inconsistencies.len()));
+  out
+}
+
+fn render_line(change: &GroupedChange, with_configuration: bool, color: bool) -> String {
+  let coordinate = &change.coordinate;
+  let suffix = match with_configuration {
+    true => format!(" [{}]", change.configuration),
+    false => String::new(),
+  };
+
+  if let Some(ack) = &change.acknowledged {
+    let line = format!(
+      "  {} {} -> {} (acknowledged: {}){}",
+      coordinate, change.version_before, change.version_after, ack.reason, suffix
+    );
+    return paint(&line, DIM, color);
+  }
+
+  match change.kind {
+    ChangeKind::Added => paint(
+      &format!("+ {} {}{}", coordinate, change.version_after, suffix),
+      GREEN,
+      color,
+    ),
+    ChangeKind::Removed => paint(
+      &format!("- {} {}{}", coordinate, change.version_before, suffix),
+      RED,
+      color,
+    ),
+    ChangeKind::Downgraded => paint(
+      &format!(
+        "~ {} {} -> {} (downgrade){}",
+        coordinate, change.version_before, change.version_after, suffix
+      ),
+      YELLOW,
+      color,
+    ),
+    _ => paint(
+      &format!(
+        "~ {} {} -> {}{}",
+        coordinate, change.version_before, change.version_after, suffix
+      ),
+      YELLOW,
+      color,
+    ),
+  }
+}
+
+fn paint(text: &str, style: &str, color: bool) -> String {
+  match color {
+    true => format!("{}{}{}", style, text, RESET),
+    false => text.to_string(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::configurations::ConfigurationEvent;
+  use crate::ordering::group;
+  use crate::types::test_support::dep;
+  use crate::types::{Acknowledged, ProcessedDependencyObject};
+
+  fn render_values(values: &[ProcessedDependencyObject], group_by: GroupBy, color: bool) -> String {
+    render(&group(values, group_by, &[Vec::new(), Vec::new()]), group_by, color)
+  }
+
+  #[test]
+  fn it_groups_changes_by_configuration() {
+    let values = vec![
+      dep("dep_ns:added", vec![("runtimeClasspath", "N/A", "1.0.0")]),
+      dep("dep_ns:removed", vec![("compileClasspath", "2.0.0", "N/A")]),
+      dep(
+        "dep_ns:bumped",
+        vec![
+          ("compileClasspath", "1.0.0", "1.1.0"),
+          ("runtimeClasspath", "1.0.0", "1.0.0"),
+        ],
+      ),
+    ];
+
+    let out = render_values(&values, GroupBy::Configuration, false);
+
+    assert_eq!(
+      out,
+      "compileClasspath\n  - dep_ns:removed 2.0.0\n  ~ dep_ns:bumped 1.0.0 -> 1.1.0\n\n\
+       runtimeClasspath\n  + dep_ns:added 1.0.0\n\n"
+    );
+  }
+
+  #[test]
+  fn it_labels_lines_with_their_configuration_in_other_groupings() {
+    let values = vec![
+      dep("dep_ns:added", vec![("runtimeClasspath", "N/A", "1.0.0")]),
+      dep("dep_ns:bumped", vec![("compileClasspath", "2.0.0", "1.0.0")]),
+    ];
+
+    assert_eq!(
+      render_values(&values, GroupBy::Group, false),
+      "dep_ns\n  + dep_ns:added 1.0.0 [runtimeClasspath]\n  ~ dep_ns:bumped 2.0.0 -> 1.0.0 (downgrade) [compileClasspath]\n\n"
+    );
+  }
+
+  #[test]
+  fn it_colours_lines_only_when_enabled() {
+    let values = vec![dep("dep_ns:added", vec![("runtimeClasspath", "N/A", "1.0.0")])];
+
+    assert!(
+      render_values(&values, GroupBy::Configuration, true).contains(&format!("{}+ dep_ns:added 1.0.0{}", GREEN, RESET))
+    );
+    assert!(!render_values(&values, GroupBy::Configuration, false).contains('\x1b'));
+  }
+
+  #[test]
+  fn it_dims_acknowledged_changes() {
+    let mut values = vec![dep("dep_ns:bumped", vec![("runtimeClasspath", "1.0.0", "2.0.0")])];
+    values[0].gradle_versions[0].acknowledged = Some(Acknowledged {
+      reason: "Reviewed in #1".to_string(),
+      expires: None,
+    });
+
+    assert_eq!(
+      render_values(&values, GroupBy::Configuration, true),
+      format!(
+        "{}runtimeClasspath{}\n  {}  dep_ns:bumped 1.0.0 -> 2.0.0 (acknowledged: Reviewed in #1){}\n\n",
+        BOLD, RESET, DIM, RESET
+      )
+    );
+  }
+
+  #[test]
+  fn it_renders_the_summary() {
+    let values = vec![
+      dep("dep_ns:added", vec![("runtimeClasspath", "N/A", "1.0.0")]),
+      dep("dep_ns:bumped", vec![("runtimeClasspath", "1.0.0", "2.0.0")]),
+    ];
+
+    assert_eq!(
+      render_summary(&crate::summary::summarize(&values), false),
+      "2 of 2 dependencies changed (1 before, 2 after) in 1 configurations\n\
+       \x20 +1 added  -0 removed  ~1 upgraded  ~0 downgraded  ~0 other\n\
+       \x20 runtimeClasspath: 1 -> 2 dependencies (+1 -0 ~1)\n\
+       Largest jumps\n\
+       \x20 dep_ns:bumped 1.0.0 -> 2.0.0 (major)\n\n"
+    );
+  }
+
+  #[test]
+  fn it_renders_configuration_events() {
+    let events = vec![
+      ConfigurationEvent::Renamed {
+        from: "productionRuntimeClasspath".to_string(),
+        to: "runtimeClasspath".to_string(),
+        similarity: 0.875,
+      },
+      ConfigurationEvent::Added {
+        gradle_config_name: "testFixturesRuntimeClasspath".to_string(),
+        dependencies: 3,
+      },
+    ];
+
+    assert_eq!(
+      render_configuration_events(&events, false),
+      "Configurations\n  ~ productionRuntimeClasspath -> runtimeClasspath (renamed, 88% similar)\n\
+       \x20 + testFixturesRuntimeClasspath (3 dependencies)\n\n"
+    );
+    assert_eq!(render_configuration_events(&[], false), "");
+  }
+
+  #[test]
+  fn it_reports_when_nothing_changed() {
+    let values = vec![dep("dep_ns:same", vec![("runtimeClasspath", "1.0.0", "1.0.0")])];
+
+    assert_eq!(
+      render_values(&values, GroupBy::Configuration, false),
+      "No dependency changes.\n"
+    );
+  }
+}
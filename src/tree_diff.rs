@@ -0,0 +1,238 @@
+use rustc_hash::FxHashSet;
+use serde::Serialize;
+
+use crate::types::{ChangeKind, DependencyTree, TreeNode, NOT_APPLICABLE};
+
+/// Merged before/after dependency tree of a single Gradle configuration.
+#[derive(Debug, Serialize)]
+pub struct ConfigurationTreeDiff {
+  pub gradle_config_name: String,
+  pub has_changes: bool,
+  pub roots: Vec<TreeDiffNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TreeDiffNode {
+  pub coordinate: String,
+  pub version_before: String,
+  pub version_after: String,
+  pub kind: ChangeKind,
+  /// Whether this node or any node of its subtree changed.
+  pub has_changes: bool,
+  pub children: Vec<TreeDiffNode>,
+}
+
+/// Merges the trees of each configuration before and after. \
+/// Children are matched by coordinate: nodes only present before are `removed`,
+/// nodes only present after are `added`, and their whole subtree is marked the same way.
+/// Repeated `(*)` nodes are matched without their children, which Gradle omits.
+pub fn diff(before: &[DependencyTree], after: &[DependencyTree]) -> Vec<ConfigurationTreeDiff> {
+  let mut res: Vec<ConfigurationTreeDiff> = Vec::new();
+  let mut done: FxHashSet<&str> = FxHashSet::default();
+
+  for tree_before in before.iter() {
+    let tree_after = after
+      .iter()
+      .find(|t| t.gradle_config_name == tree_before.gradle_config_name);
+
+    done.insert(&tree_before.gradle_config_name);
+
+    let roots = match tree_after {
+      Some(tree_after) => merge_nodes(&tree_before.roots, &tree_after.roots),
+      None => tree_before
+        .roots
+        .iter()
+        .map(|n| one_sided(n, ChangeKind::Removed))
+        .collect(),
+    };
+
+    res.push(configuration_diff(&tree_before.gradle_config_name, roots));
+  }
+
+  /* cover configurations that are unique to after */
+  for tree_after in after
+    .iter()
+    .filter(|t| !done.contains(t.gradle_config_name.as_str()))
+  {
+    let roots = tree_after
+      .roots
+      .iter()
+      .map(|n| one_sided(n, ChangeKind::Added))
+      .collect();
+
+    res.push(configuration_diff(&tree_after.gradle_config_name, roots));
+  }
+
+  res
+}
+
+fn configuration_diff(name: &str, roots: Vec<TreeDiffNode>) -> ConfigurationTreeDiff {
+  ConfigurationTreeDiff {
+    gradle_config_name: name.to_string(),
+    has_changes: roots.iter().any(|n| n.has_changes),
+    roots,
+  }
+}
+
+fn merge_nodes(before: &[TreeNode], after: &[TreeNode]) -> Vec<TreeDiffNode> {
+  let mut res: Vec<TreeDiffNode> = Vec::new();
+  let mut matched: Vec<bool> = vec![false; after.len()];
+
+  for node_before in before.iter() {
+    let coordinate = node_before.coordinate();
+    let idx_after = after
+      .iter()
+      .zip(matched.iter())
+      .position(|(n, matched)| !matched && n.coordinate() == coordinate);
+
+    match idx_after {
+      Some(idx) => {
+        matched[idx] = true;
+        let node_after = &after[idx];
+        /* the children of a `(*)` node are printed elsewhere, so they can't be told missing */
+        let children = match node_before.repeated || node_after.repeated {
+          true => Vec::new(),
+          false => merge_nodes(&node_before.children, &node_after.children),
+        };
+        let kind = ChangeKind::of(&node_before.version, &node_after.version);
+
+        res.push(TreeDiffNode {
+          coordinate,
+          version_before: node_before.version.clone(),
+          version_after: node_after.version.clone(),
+          kind,
+          has_changes: kind.is_change() || children.iter().any(|c| c.has_changes),
+          children,
+        });
+      }
+      None => res.push(one_sided(node_before, ChangeKind::Removed)),
+    }
+  }
+
+  /* cover nodes that are unique to after */
+  after
+    .iter()
+    .zip(matched.iter())
+    .filter(|(_, matched)| !**matched)
+    .for_each(|(n, _)| res.push(one_sided(n, ChangeKind::Added)));
+
+  res
+}
+
+/// Converts a subtree present on only one side, `kind` being `Added` or `Removed`.
+fn one_sided(node: &TreeNode, kind: ChangeKind) -> TreeDiffNode {
+  let (version_before, version_after) = match kind {
+    ChangeKind::Added => (NOT_APPLICABLE.to_string(), node.version.clone()),
+    _ => (node.version.clone(), NOT_APPLICABLE.to_string()),
+  };
+
+  TreeDiffNode {
+    coordinate: node.coordinate(),
+    version_before,
+    version_after,
+    kind,
+    has_changes: true,
+    children: node
+      .children
+      .iter()
+      .map(|c| one_sided(c, kind))
+      .collect(),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::test_support::node;
+
+  fn tree(name: &str, roots: Vec<TreeNode>) -> DependencyTree {
+    DependencyTree {
+      gradle_config_name: name.to_string(),
+      roots,
+    }
+  }
+
+  #[test]
+  fn it_marks_only_changed_subtrees() {
+    let before = vec![tree(
+      "runtimeClasspath",
+      vec![
+        node(
+          "dep_ns:a",
+          "1.0",
+          vec![node("dep_ns:b", "1.0", vec![node("dep_ns:c", "1.0", vec![])])],
+        ),
+        node("dep_ns:d", "1.0", vec![node("dep_ns:e", "1.0", vec![])]),
+      ],
+    )];
+    let after = vec![tree(
+      "runtimeClasspath",
+      vec![
+        node(
+          "dep_ns:a",
+          "1.0",
+          vec![node("dep_ns:b", "1.0", vec![node("dep_ns:c", "1.1", vec![])])],
+        ),
+        node("dep_ns:d", "1.0", vec![node("dep_ns:e", "1.0", vec![])]),
+      ],
+    )];
+
+    let res = diff(&before, &after);
+
+    assert_eq!(res.len(), 1);
+    assert!(res[0].has_changes);
+
+    let a = &res[0].roots[0];
+    assert_eq!(a.kind, ChangeKind::Unchanged);
+    assert!(a.has_changes);
+    assert_eq!(a.children[0].children[0].kind, ChangeKind::Upgraded);
+
+    assert!(!res[0].roots[1].has_changes);
+  }
+
+  #[test]
+  fn it_marks_one_sided_nodes_and_configurations() {
+    let before = vec![tree(
+      "compileClasspath",
+      vec![node("dep_ns:a", "1.0", vec![node("dep_ns:b", "1.0", vec![])])],
+    )];
+    let after = vec![
+      tree("compileClasspath", vec![node("dep_ns:c", "2.0", vec![])]),
+      tree("runtimeClasspath", vec![node("dep_ns:c", "2.0", vec![])]),
+    ];
+
+    let res = diff(&before, &after);
+
+    assert_eq!(res.len(), 2);
+    let compile = &res[0];
+    assert_eq!(compile.roots[0].kind, ChangeKind::Removed);
+    assert_eq!(compile.roots[0].children[0].kind, ChangeKind::Removed);
+    assert_eq!(compile.roots[1].kind, ChangeKind::Added);
+    assert_eq!(compile.roots[1].version_before, "N/A");
+
+    assert_eq!(res[1].gradle_config_name, "runtimeClasspath");
+    assert_eq!(res[1].roots[0].kind, ChangeKind::Added);
+  }
+
+  #[test]
+  fn it_does_not_diff_the_children_of_repeated_nodes() {
+    let mut repeated = node("dep_ns:b", "1.0", vec![]);
+    repeated.repeated = true;
+    let before = vec![tree("runtimeClasspath", vec![node("dep_ns:a", "1.0", vec![repeated])])];
+    let after = vec![tree(
+      "runtimeClasspath",
+      vec![node(
+        "dep_ns:a",
+        "1.0",
+        vec![node("dep_ns:b", "1.0", vec![node("dep_ns:c", "1.0", vec![])])],
+      )],
+    )];
+
+    let res = diff(&before, &after);
+
+    let b = &res[0].roots[0].children[0];
+    assert_eq!(b.kind, ChangeKind::Unchanged);
+    assert!(b.children.is_empty());
+    assert!(!res[0].has_changes);
+  }
+}
@@ -0,0 +1,143 @@
+use clap::ValueEnum;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use version_compare::{compare, Cmp};
+
+use super::NOT_APPLICABLE;
+
+/// The kind of change a dependency went through in a single Gradle configuration,
+/// derived from its final version before and after. \
+/// Variants are ordered as declared, as used by `--sort-by change-kind`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+  Added,
+  Removed,
+  Upgraded,
+  Downgraded,
+  /// Versions differ but cannot be ordered (e.g. non-numeric qualifiers).
+  Changed,
+  Unchanged,
+}
+
+impl ChangeKind {
+  /// Classifies a `version_before` -> `version_after` transition,
+  /// where `"N/A"` means the dependency is absent on that side.
+  pub fn of(version_before: &str, version_after: &str) -> Self {
+    match (version_before == NOT_APPLICABLE, version_after == NOT_APPLICABLE) {
+      (true, true) => ChangeKind::Unchanged,
+      (true, false) => ChangeKind::Added,
+      (false, true) => ChangeKind::Removed,
+      (false, false) if version_before == version_after => ChangeKind::Unchanged,
+      (false, false) => match compare(version_after, version_before) {
+        Ok(Cmp::Gt) => ChangeKind::Upgraded,
+        Ok(Cmp::Lt) => ChangeKind::Downgraded,
+        _ => ChangeKind::Changed,
+      },
+    }
+  }
+
+  pub fn is_change(&self) -> bool {
+    *self != ChangeKind::Unchanged
+  }
+}
+
+/// The most significant version component that differs between two versions,
+/// read from their leading numeric components (`major.minor.patch`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverLevel {
+  None,
+  /// Only a fourth or later component, or a qualifier (e.g. `-jre`, `.Final`), differs.
+  Other,
+  Patch,
+  Minor,
+  Major,
+}
+
+impl SemverLevel {
+  /// Returns `None` when either side is `"N/A"` or the versions are equal.
+  pub fn of(version_before: &str, version_after: &str) -> Self {
+    if version_before == NOT_APPLICABLE || version_after == NOT_APPLICABLE || version_before == version_after {
+      return SemverLevel::None;
+    }
+
+    let parts_before = numeric_parts(version_before);
+    let parts_after = numeric_parts(version_after);
+
+    for idx in 0..3 {
+      if parts_before.get(idx) != parts_after.get(idx) {
+        return match idx {
+          0 => SemverLevel::Major,
+          1 => SemverLevel::Minor,
+          _ => SemverLevel::Patch,
+        };
+      }
+    }
+
+    SemverLevel::Other
+  }
+}
+
+impl fmt::Display for SemverLevel {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let val = match self {
+      SemverLevel::None => "none",
+      SemverLevel::Other => "other",
+      SemverLevel::Patch => "patch",
+      SemverLevel::Minor => "minor",
+      SemverLevel::Major => "major",
+    };
+
+    write!(f, "{}", val)
+  }
+}
+
+/// Leading numeric components of a version, e.g. `[32, 1, 2]` for `32.1.2-jre`.
+pub fn numeric_parts(version: &str) -> Vec<u64> {
+  version
+    .split(['.', '-', '_', '+'])
+    .map_while(|part| part.parse::<u64>().ok())
+    .collect()
+}
+
+impl fmt::Display for ChangeKind {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let val = match self {
+      ChangeKind::Added => "added",
+      ChangeKind::Removed => "removed",
+      ChangeKind::Upgraded => "upgraded",
+      ChangeKind::Downgraded => "downgraded",
+      ChangeKind::Changed => "changed",
+      ChangeKind::Unchanged => "unchanged",
+    };
+
+    write!(f, "{}", val)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn it_classifies_version_transitions() {
+    assert_eq!(ChangeKind::of("N/A", "1.0.0"), ChangeKind::Added);
+    assert_eq!(ChangeKind::of("1.0.0", "N/A"), ChangeKind::Removed);
+    assert_eq!(ChangeKind::of("1.0.0", "1.0.0"), ChangeKind::Unchanged);
+    assert_eq!(ChangeKind::of("1.0.0", "1.2.0"), ChangeKind::Upgraded);
+    assert_eq!(ChangeKind::of("5.9.3", "5.9.2"), ChangeKind::Downgraded);
+  }
+
+  #[test]
+  fn it_detects_the_semver_level_of_a_change() {
+    assert_eq!(SemverLevel::of("31.1-jre", "32.1.2-jre"), SemverLevel::Major);
+    assert_eq!(SemverLevel::of("6.0.11", "6.1.0"), SemverLevel::Minor);
+    assert_eq!(SemverLevel::of("2.15.2", "2.15.3"), SemverLevel::Patch);
+    assert_eq!(SemverLevel::of("5.6.15.Final", "5.6.15.1"), SemverLevel::Other);
+    assert_eq!(SemverLevel::of("1.0", "1.0.1"), SemverLevel::Patch);
+    assert_eq!(SemverLevel::of("N/A", "1.0.0"), SemverLevel::None);
+    assert_eq!(SemverLevel::of("1.0.0", "1.0.0"), SemverLevel::None);
+  }
+}
@@ -1,4 +1,7 @@
+use crate::types::version::{deserialize_pinned, deserialize_transitive, not_applicable, nullable};
+use crate::types::ChangeKind;
 use crate::Version;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +11,13 @@ pub struct Dependency {
   pub gradle_entries: Vec<GradleEntry>,
 }
 
+impl Dependency {
+  /// `group:artifact`, the key of the dependency maps.
+  pub fn coordinate(&self) -> String {
+    format!("{}:{}", self.namespace, self.name)
+  }
+}
+
 #[derive(Debug)]
 pub struct ParsedDependency {
   pub name: String,
@@ -16,36 +26,51 @@ pub struct ParsedDependency {
   pub pinned: Version,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ParsedDependency {
+  pub fn coordinate(&self) -> String {
+    format!("{}:{}", self.namespace, self.name)
+  }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessedDependencyObject {
   pub dependency: ProcessedDependency,
+  /// Whether the version of any configuration differs before and after.
   pub changed: bool,
   pub gradle_versions: Vec<GradleList>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProcessedDependency {
+  /// Artifact name.
   pub name: String,
+  /// Group of the artifact.
   pub namespace: String,
   pub gradle_entries_before: Vec<GradleEntry>,
   pub gradle_entries_after: Vec<GradleEntry>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GradleEntry {
   pub gradle_config_name: String,
   pub versions: Versions,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Versions {
+  /// Versions requested by other dependencies, `null` when only a pinned version was printed.
+  #[serde(deserialize_with = "deserialize_transitive")]
+  #[schemars(with = "Vec<Option<String>>")]
   pub transitive: Vec<Version>,
+  /// Version the configuration resolved to (`a:b:1.0 -> 1.1`), if any.
+  #[serde(deserialize_with = "deserialize_pinned", default)]
+  #[schemars(with = "Option<String>")]
   pub pinned: Version,
 }
 
 impl Versions {
   pub fn transitive_contains(&self, val: &Version) -> bool {
-    return self.transitive.contains(val);
+    self.transitive.contains(val)
   }
 }
 
@@ -53,7 +78,6 @@ impl Versions {
 pub enum LineParseResult {
   Parsed,
   Skip,
-  End,
 }
 
 #[derive(Debug)]
@@ -68,9 +92,44 @@ pub struct TempList {
   pub version: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct GradleList {
   pub gradle_config_name: String,
+  /// Final version before, `null` when the dependency is absent (`"N/A"` in templates).
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
   pub version_before: String,
+  /// Final version after, `null` when the dependency is absent (`"N/A"` in templates).
+  #[serde(with = "nullable", default = "not_applicable")]
+  #[schemars(with = "Option<String>")]
   pub version_after: String,
+  /// Set when the change matches an entry of the acknowledgements file.
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub acknowledged: Option<Acknowledged>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Acknowledged {
+  pub reason: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub expires: Option<String>,
+}
+
+impl GradleList {
+  pub fn new(gradle_config_name: &str, version_before: &str, version_after: &str) -> Self {
+    Self {
+      gradle_config_name: gradle_config_name.to_string(),
+      version_before: version_before.to_string(),
+      version_after: version_after.to_string(),
+      acknowledged: None,
+    }
+  }
+
+  pub fn is_acknowledged(&self) -> bool {
+    self.acknowledged.is_some()
+  }
+
+  pub fn change_kind(&self) -> ChangeKind {
+    ChangeKind::of(&self.version_before, &self.version_after)
+  }
 }
@@ -1,8 +1,14 @@
+mod change;
 mod dependency;
+#[cfg(test)]
+pub mod test_support;
+mod tree;
 mod version;
 
+pub use change::{numeric_parts, ChangeKind, SemverLevel};
 pub use dependency::{
-  DepParseResult, Dependency, GradleEntry, GradleList, LineParseResult, ParsedDependency, ProcessedDependency,
-  ProcessedDependencyObject, TempList, Versions,
+  Acknowledged, DepParseResult, Dependency, GradleEntry, GradleList, LineParseResult, ParsedDependency,
+  ProcessedDependency, ProcessedDependencyObject, TempList, Versions,
 };
-pub use version::Version;
+pub use tree::{DependencyTree, TreeNode};
+pub use version::{not_applicable, nullable, Version, NOT_APPLICABLE};
@@ -0,0 +1,42 @@
+use super::{GradleList, ProcessedDependency, ProcessedDependencyObject, TempList, TreeNode};
+
+/// Changed dependency `group:artifact` with a `(configuration, version before, version after)` list for each
+/// configuration.
+pub fn dep(coordinate: &str, lists: Vec<(&str, &str, &str)>) -> ProcessedDependencyObject {
+  let (namespace, name) = coordinate.split_once(':').unwrap();
+
+  ProcessedDependencyObject {
+    dependency: ProcessedDependency {
+      name: name.to_string(),
+      namespace: namespace.to_string(),
+      gradle_entries_before: Vec::new(),
+      gradle_entries_after: Vec::new(),
+    },
+    changed: true,
+    gradle_versions: lists
+      .into_iter()
+      .map(|(config, before, after)| GradleList::new(config, before, after))
+      .collect(),
+  }
+}
+
+/// Resolved `version` of a dependency in the configuration `config`.
+pub fn temp(config: &str, version: &str) -> TempList {
+  TempList {
+    gradle_config_name: config.to_string(),
+    version: version.to_string(),
+  }
+}
+
+/// Dependency tree node `group:artifact` resolved to `version`, with the given children.
+pub fn node(coordinate: &str, version: &str, children: Vec<TreeNode>) -> TreeNode {
+  let (namespace, name) = coordinate.split_once(':').unwrap();
+
+  TreeNode {
+    name: name.to_string(),
+    namespace: namespace.to_string(),
+    version: version.to_string(),
+    repeated: false,
+    children,
+  }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+/// The dependency tree of a single Gradle configuration, as printed by the `dependencies` task.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyTree {
+  pub gradle_config_name: String,
+  pub roots: Vec<TreeNode>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TreeNode {
+  pub name: String,
+  pub namespace: String,
+  /// Version selected for this node (pinned if present, else transitive, else `"N/A"`).
+  pub version: String,
+  /// Marked with `(*)`: the subtree was already printed elsewhere and its children are omitted.
+  pub repeated: bool,
+  pub children: Vec<TreeNode>,
+}
+
+impl TreeNode {
+  pub fn coordinate(&self) -> String {
+    format!("{}:{}", self.namespace, self.name)
+  }
+}
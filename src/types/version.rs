@@ -1,11 +1,18 @@
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
-#[derive(Debug, PartialEq, Clone, Deserialize)]
-#[serde(untagged)]
+/// Placeholder of an absent version in version strings (e.g. `GradleList::version_before`),
+/// written as `null` in JSON.
+pub const NOT_APPLICABLE: &str = "N/A";
+
+/// A version as printed by Gradle. \
+/// In JSON it is a string, or `null` when not applicable; whether it is transitive or pinned
+/// follows from the field holding it (see `Versions`).
+#[derive(Debug, PartialEq, Clone, Default)]
 pub enum Version {
   Transitive(String),
   Pinned(String),
+  #[default]
   NotApplicable,
 }
 
@@ -19,7 +26,7 @@ impl fmt::Display for Version {
   fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
     match self {
       Version::Transitive(val) | Version::Pinned(val) => write!(f, "{}", val),
-      Version::NotApplicable => write!(f, "N/A"),
+      Version::NotApplicable => write!(f, "{}", NOT_APPLICABLE),
     }
   }
 }
@@ -27,11 +34,67 @@ impl fmt::Display for Version {
 impl Serialize for Version {
   fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
   where
-    S: serde::Serializer,
+    S: Serializer,
   {
     match self {
-      Version::Transitive(val) | Version::Pinned(val) => serializer.serialize_str(val),
-      Version::NotApplicable => serializer.serialize_str("N/A"),
+      Version::Transitive(val) | Version::Pinned(val) => serializer.serialize_some(val),
+      Version::NotApplicable => serializer.serialize_none(),
     }
   }
 }
+
+/// Deserialises `Versions::transitive`.
+pub fn deserialize_transitive<'de, D>(deserializer: D) -> Result<Vec<Version>, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  let versions: Vec<Option<String>> = Vec::deserialize(deserializer)?;
+
+  Ok(
+    versions
+      .into_iter()
+      .map(|v| match v {
+        Some(v) => Version::Transitive(v),
+        None => Version::NotApplicable,
+      })
+      .collect(),
+  )
+}
+
+/// Deserialises `Versions::pinned`.
+pub fn deserialize_pinned<'de, D>(deserializer: D) -> Result<Version, D::Error>
+where
+  D: Deserializer<'de>,
+{
+  Ok(match Option::<String>::deserialize(deserializer)? {
+    Some(v) => Version::Pinned(v),
+    None => Version::NotApplicable,
+  })
+}
+
+pub fn not_applicable() -> String {
+  NOT_APPLICABLE.to_string()
+}
+
+/// (De)serialises a version string, writing the `"N/A"` placeholder as `null`.
+pub mod nullable {
+  use super::NOT_APPLICABLE;
+  use serde::{Deserialize, Deserializer, Serializer};
+
+  pub fn serialize<S>(version: &str, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    match version {
+      NOT_APPLICABLE => serializer.serialize_none(),
+      _ => serializer.serialize_some(version),
+    }
+  }
+
+  pub fn deserialize<'de, D>(deserializer: D) -> Result<String, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_else(|| NOT_APPLICABLE.to_string()))
+  }
+}